@@ -1,6 +1,6 @@
 use proc_macro::TokenStream;
 use quote::quote;
-use syn::{parse, ExprCall, ItemStruct, PathArguments, Type};
+use syn::{parse, ExprCall, Field, ItemStruct, Lit, Meta, NestedMeta, PathArguments, Type};
 
 //
 // Type -> Kind -> Layout
@@ -36,7 +36,7 @@ fn type_str_to_kind_string(type_str: &str) -> String {
         _ => {
             eprintln!(
                 "Field type not supported in layout kind conversion: {}",
-                type_str.to_string()
+                type_str
             );
             panic!()
         }
@@ -99,6 +99,8 @@ fn kind_to_layout(kind: Kind, layout_name: String) -> proc_macro2::TokenStream {
                     name: String::from(#layout_name),
                     kind: #field_kind_enum_string,
                     fields: None,
+                    default: None,
+                    field_number: None,
                 }
             }
         }
@@ -111,6 +113,8 @@ fn kind_to_layout(kind: Kind, layout_name: String) -> proc_macro2::TokenStream {
                     fields: Some(vec![
                         #child_layout
                     ]),
+                    default: None,
+                    field_number: None,
                 }
             }
         }
@@ -119,7 +123,14 @@ fn kind_to_layout(kind: Kind, layout_name: String) -> proc_macro2::TokenStream {
                 format!("{}::get_layout()", struct_name_string).as_str(),
             )
             .expect("Could not parse function call string");
-            quote! { #function_call }
+            // A nested struct's layout is named after the field holding it,
+            // like every other field, rather than after its type.
+            quote! {
+                Layout {
+                    name: String::from(#layout_name),
+                    ..#function_call
+                }
+            }
         }
     }
 }
@@ -134,6 +145,15 @@ fn item_struct_to_layout_token_stream(item_struct: ItemStruct) -> proc_macro2::T
             .to_string();
         let field_kind = type_to_kind(field.ty.clone());
         let field_layout = kind_to_layout(field_kind, field_name);
+        let field_layout = match field_proto_number(field) {
+            Some(number) => quote! {
+                Layout {
+                    field_number: Some(#number),
+                    ..#field_layout
+                }
+            },
+            None => field_layout,
+        };
         field_layout_token_streams.push(field_layout);
     }
     let struct_name = item_struct.ident.clone().to_string();
@@ -146,30 +166,112 @@ fn item_struct_to_layout_token_stream(item_struct: ItemStruct) -> proc_macro2::T
                     #(#field_layout_token_streams),*
                 ]
             ),
+            default: None,
+            field_number: None,
         }
     };
     layout_token_stream
 }
 
+// Reads `#[proto(number = 3)]` from a field.
+fn field_proto_number(field: &Field) -> Option<u32> {
+    let attribute = field
+        .attrs
+        .iter()
+        .find(|attribute| attribute.path.is_ident("proto"))?;
+    let nested = match attribute.parse_meta() {
+        Ok(Meta::List(list)) => list.nested,
+        _ => panic!("Expected #[proto(number = ..)]"),
+    };
+    match nested.first() {
+        Some(NestedMeta::Meta(Meta::NameValue(name_value)))
+            if nested.len() == 1 && name_value.path.is_ident("number") =>
+        {
+            match &name_value.lit {
+                Lit::Int(number) => Some(
+                    number
+                        .base10_parse::<u32>()
+                        .expect("Could not parse proto field number"),
+                ),
+                _ => panic!("Proto field number must be an integer"),
+            }
+        }
+        _ => panic!("Expected #[proto(number = ..)]"),
+    }
+}
+
+// `proto` is not a real attribute, so it must not reach the emitted struct.
+fn strip_proto_attributes(mut item_struct: ItemStruct) -> ItemStruct {
+    for field in item_struct.fields.iter_mut() {
+        field
+            .attrs
+            .retain(|attribute| !attribute.path.is_ident("proto"));
+    }
+    item_struct
+}
+
+fn component_arguments(metadata: TokenStream) -> Vec<String> {
+    let arguments = metadata
+        .to_string()
+        .split(',')
+        .map(|argument| argument.trim().to_string())
+        .filter(|argument| !argument.is_empty())
+        .collect::<Vec<_>>();
+    for argument in arguments.iter() {
+        if argument != "default" && argument != "register" {
+            eprintln!("Unsupported component argument: {}", argument);
+            panic!()
+        }
+    }
+    arguments
+}
+
 #[proc_macro_attribute]
-pub fn component(_metadata: TokenStream, input: TokenStream) -> TokenStream {
-    let item_struct =
-        parse::<ItemStruct>(TokenStream::from(input)).expect("Could not parse item struct");
+pub fn component(metadata: TokenStream, input: TokenStream) -> TokenStream {
+    let arguments = component_arguments(metadata);
+    let item_struct = parse::<ItemStruct>(input).expect("Could not parse item struct");
     let item_struct_name = item_struct.ident.clone();
     let item_struct_layout_token_stream = item_struct_to_layout_token_stream(item_struct.clone());
+    let item_struct = strip_proto_attributes(item_struct);
     let item_struct_name_string = item_struct_name.to_string();
+    let layout_defaults_token_stream = if arguments.iter().any(|argument| argument == "default") {
+        quote! {
+            let default_bytes = project_kind::bincode::serialize(&<Self as Default>::default())
+                .expect("Could not encode default instance");
+            let layout = layout
+                .with_field_defaults(&default_bytes)
+                .expect("Could not decode default instance");
+        }
+    } else {
+        quote! {}
+    };
+    let registration_token_stream = if arguments.iter().any(|argument| argument == "register") {
+        quote! {
+            project_kind::inventory::submit! {
+                project_kind::RegisteredComponent {
+                    name: #item_struct_name_string,
+                    get_layout: <#item_struct_name as project_kind::HasLayout>::get_layout,
+                }
+            }
+        }
+    } else {
+        quote! {}
+    };
     let expanded = quote! {
         #[repr(C)]
         #[derive(serde::Serialize, serde::Deserialize)]
         #item_struct
         impl project_kind::HasLayout for #item_struct_name {
             fn get_layout() -> Layout {
-                #item_struct_layout_token_stream
+                let layout = #item_struct_layout_token_stream;
+                #layout_defaults_token_stream
+                layout
             }
             fn get_name(&self) -> String {
                 String::from(#item_struct_name_string)
             }
         }
+        #registration_token_stream
     };
     TokenStream::from(expanded)
 }
@@ -0,0 +1,8 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use project_kind::LayoutKind;
+
+fuzz_target!(|data: &[u8]| {
+    project_kind_fuzz::check_decode(&project_kind_fuzz::layout(LayoutKind::F64), data);
+});
@@ -0,0 +1,108 @@
+use project_kind::{Layout, LayoutKind};
+
+fn leaf(name: &str, kind: LayoutKind) -> Layout {
+    Layout {
+        name: String::from(name),
+        kind,
+        fields: None,
+        default: None,
+        field_number: None,
+    }
+}
+
+fn array(name: &str, element: Layout) -> Layout {
+    Layout {
+        name: String::from(name),
+        kind: LayoutKind::Array,
+        fields: Some(vec![element]),
+        default: None,
+        field_number: None,
+    }
+}
+
+fn structure(name: &str, fields: Vec<Layout>) -> Layout {
+    Layout {
+        name: String::from(name),
+        kind: LayoutKind::Struct,
+        fields: Some(fields),
+        default: None,
+        field_number: None,
+    }
+}
+
+// The layout exercised by each fuzz target. Arrays and structs nest the other
+// kinds so that corrupt length prefixes show up below the top level.
+pub fn layout(kind: LayoutKind) -> Layout {
+    match kind {
+        LayoutKind::Array => array(
+            "items",
+            structure(
+                "Item",
+                vec![
+                    leaf("id", LayoutKind::U16),
+                    array("tags", leaf("tags", LayoutKind::String)),
+                    leaf("active", LayoutKind::Bool),
+                ],
+            ),
+        ),
+        LayoutKind::Struct => structure(
+            "Person",
+            vec![
+                leaf("name", LayoutKind::String),
+                leaf("alive", LayoutKind::Bool),
+                leaf("age", LayoutKind::U8),
+                leaf("level", LayoutKind::U16),
+                leaf("gold", LayoutKind::U32),
+                leaf("experience", LayoutKind::U64),
+                leaf("mood", LayoutKind::I8),
+                leaf("x", LayoutKind::I16),
+                leaf("y", LayoutKind::I32),
+                leaf("z", LayoutKind::I64),
+                leaf("speed", LayoutKind::F32),
+                leaf("weight", LayoutKind::F64),
+                array("inventory", leaf("inventory", LayoutKind::U8)),
+                structure("home", vec![leaf("city", LayoutKind::String)]),
+            ],
+        ),
+        kind => leaf("value", kind),
+    }
+}
+
+// Runs every decoding entry point over `data`. Any panic is a finding; errors
+// are expected, and the decoders must agree on which inputs are valid.
+pub fn check_decode(layout: &Layout, data: &[u8]) {
+    let serialized = project_kind::serialize(layout, data);
+    let annotation = layout.annotate(data);
+    let annotated_ok = match &annotation.divergence {
+        Some(divergence) => divergence
+            .message
+            .ends_with("trailing bytes after end of layout"),
+        None => true,
+    };
+    assert_eq!(serialized.is_ok(), annotated_ok);
+    assert_eq!(
+        project_kind::get_path(layout, data, "").is_ok(),
+        annotated_ok
+    );
+    assert_eq!(project_kind::diff(layout, data, data).is_ok(), annotated_ok);
+    assert_eq!(
+        project_kind::serialize_msgpack(layout, data).is_ok(),
+        annotated_ok
+    );
+    assert_eq!(
+        project_kind::serialize_cbor(layout, data).is_ok(),
+        annotated_ok
+    );
+    assert_eq!(
+        project_kind::serialize_yaml(layout, data).is_ok(),
+        annotated_ok
+    );
+    // Only structs map to protobuf messages; `data` doubles as a message.
+    if layout.kind == LayoutKind::Struct {
+        assert_eq!(
+            project_kind::serialize_protobuf(layout, data).is_ok(),
+            annotated_ok
+        );
+        let _ = project_kind::deserialize_protobuf(layout, data);
+    }
+}
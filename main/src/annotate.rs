@@ -0,0 +1,234 @@
+use std::fmt;
+
+use crate::{Layout, LayoutKind};
+
+const ROW_WIDTH: usize = 8;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Annotation {
+    pub bytes: Vec<u8>,
+    pub ranges: Vec<AnnotatedRange>,
+    pub divergence: Option<Divergence>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AnnotatedRange {
+    pub offset: usize,
+    pub length: usize,
+    pub path: String,
+    pub value: String,
+}
+
+// Where decoding stopped following the layout: truncated or invalid data, or
+// bytes left over once the layout has been consumed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Divergence {
+    pub offset: usize,
+    pub path: String,
+    pub message: String,
+}
+
+impl Layout {
+    pub fn annotate(&self, bytes: &[u8]) -> Annotation {
+        let mut annotator = Annotator {
+            bytes,
+            ranges: Vec::new(),
+        };
+        let divergence = match annotator.walk(self, "", 0) {
+            Ok(end) if end < bytes.len() => Some(Divergence {
+                offset: end,
+                path: String::new(),
+                message: format!("{} trailing bytes after end of layout", bytes.len() - end),
+            }),
+            Ok(_) => None,
+            Err(divergence) => Some(divergence),
+        };
+        Annotation {
+            bytes: bytes.to_vec(),
+            ranges: annotator.ranges,
+            divergence,
+        }
+    }
+}
+
+struct Annotator<'a> {
+    bytes: &'a [u8],
+    ranges: Vec<AnnotatedRange>,
+}
+
+fn join_path(path: &str, name: &str) -> String {
+    if path.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", path, name)
+    }
+}
+
+impl Annotator<'_> {
+    fn take(&self, path: &str, offset: usize, length: usize) -> Result<&[u8], Divergence> {
+        let remaining = self.bytes.len().saturating_sub(offset);
+        if length > remaining {
+            return Err(Divergence {
+                offset,
+                path: path.to_string(),
+                message: format!("truncated: needs {} bytes, {} remain", length, remaining),
+            });
+        }
+        Ok(&self.bytes[offset..(offset + length)])
+    }
+
+    fn push(&mut self, path: &str, offset: usize, length: usize, value: String) {
+        self.ranges.push(AnnotatedRange {
+            offset,
+            length,
+            path: path.to_string(),
+            value,
+        });
+    }
+
+    fn take_length(&mut self, path: &str, offset: usize) -> Result<usize, Divergence> {
+        let length_bytes: [u8; 8] = self.take(path, offset, 8)?.try_into().unwrap();
+        let length = usize::from_le_bytes(length_bytes);
+        self.push(&format!("{} (length)", path), offset, 8, length.to_string());
+        Ok(length)
+    }
+
+    fn walk(&mut self, layout: &Layout, path: &str, offset: usize) -> Result<usize, Divergence> {
+        match layout.kind {
+            LayoutKind::Array => {
+                let element_layout = layout.fields.as_ref().unwrap().first().unwrap();
+                let element_count = self.take_length(path, offset)?;
+                let mut offset = offset + 8;
+                for index in 0..element_count {
+                    let element_offset = offset;
+                    offset = self.walk(element_layout, &format!("{}[{}]", path, index), offset)?;
+                    if offset == element_offset {
+                        break;
+                    }
+                }
+                Ok(offset)
+            }
+            LayoutKind::Struct => {
+                let mut offset = offset;
+                for field in layout.fields.as_ref().unwrap().iter() {
+                    offset = self.walk(field, &join_path(path, &field.name), offset)?;
+                }
+                Ok(offset)
+            }
+            LayoutKind::String => {
+                let length = self.take_length(path, offset)?;
+                let value = match std::str::from_utf8(self.take(path, offset + 8, length)?) {
+                    Ok(value) => format!("{:?}", value),
+                    Err(_) => {
+                        return Err(Divergence {
+                            offset: offset + 8,
+                            path: path.to_string(),
+                            message: String::from("invalid utf-8 in string"),
+                        })
+                    }
+                };
+                self.push(path, offset + 8, length, value);
+                Ok(offset + 8 + length)
+            }
+            LayoutKind::Bool => {
+                let value = match self.take(path, offset, 1)?[0] {
+                    0 => String::from("false"),
+                    1 => String::from("true"),
+                    byte => {
+                        return Err(Divergence {
+                            offset,
+                            path: path.to_string(),
+                            message: format!("invalid bool byte {:#04x}", byte),
+                        })
+                    }
+                };
+                self.push(path, offset, 1, value);
+                Ok(offset + 1)
+            }
+            _ => {
+                let size = layout.primitive_size().unwrap();
+                let slice = self.take(path, offset, size)?;
+                let value = match layout.kind {
+                    LayoutKind::U8 => u8::from_le_bytes(slice.try_into().unwrap()).to_string(),
+                    LayoutKind::U16 => u16::from_le_bytes(slice.try_into().unwrap()).to_string(),
+                    LayoutKind::U32 => u32::from_le_bytes(slice.try_into().unwrap()).to_string(),
+                    LayoutKind::U64 => u64::from_le_bytes(slice.try_into().unwrap()).to_string(),
+                    LayoutKind::I8 => i8::from_le_bytes(slice.try_into().unwrap()).to_string(),
+                    LayoutKind::I16 => i16::from_le_bytes(slice.try_into().unwrap()).to_string(),
+                    LayoutKind::I32 => i32::from_le_bytes(slice.try_into().unwrap()).to_string(),
+                    LayoutKind::I64 => i64::from_le_bytes(slice.try_into().unwrap()).to_string(),
+                    LayoutKind::F32 => f32::from_le_bytes(slice.try_into().unwrap()).to_string(),
+                    LayoutKind::F64 => f64::from_le_bytes(slice.try_into().unwrap()).to_string(),
+                    _ => unreachable!(),
+                };
+                self.push(path, offset, size, value);
+                Ok(offset + size)
+            }
+        }
+    }
+}
+
+fn write_row(f: &mut fmt::Formatter, offset: usize, row: &[u8], label: &str) -> fmt::Result {
+    let hex = row
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let ascii = row
+        .iter()
+        .map(|byte| {
+            if byte.is_ascii_graphic() || *byte == b' ' {
+                *byte as char
+            } else {
+                '.'
+            }
+        })
+        .collect::<String>();
+    let line = format!(
+        "{:08x}  {:<hex_width$}  {:<ascii_width$}  {}",
+        offset,
+        hex,
+        ascii,
+        label,
+        hex_width = ROW_WIDTH * 3 - 1,
+        ascii_width = ROW_WIDTH,
+    );
+    writeln!(f, "{}", line.trim_end())
+}
+
+fn write_range(f: &mut fmt::Formatter, bytes: &[u8], offset: usize, label: &str) -> fmt::Result {
+    if bytes.is_empty() {
+        return write_row(f, offset, &[], label);
+    }
+    for (index, row) in bytes.chunks(ROW_WIDTH).enumerate() {
+        let label = if index == 0 { label } else { "" };
+        write_row(f, offset + index * ROW_WIDTH, row, label)?;
+    }
+    Ok(())
+}
+
+impl fmt::Display for Annotation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for range in self.ranges.iter() {
+            let bytes = &self.bytes[range.offset..(range.offset + range.length)];
+            let label = format!("{} = {}", range.path, range.value);
+            write_range(f, bytes, range.offset, &label)?;
+        }
+        if let Some(divergence) = &self.divergence {
+            if divergence.path.is_empty() {
+                writeln!(f, "!! {:08x}  {}", divergence.offset, divergence.message)?;
+            } else {
+                writeln!(
+                    f,
+                    "!! {:08x}  {}: {}",
+                    divergence.offset, divergence.path, divergence.message
+                )?;
+            }
+            let offset = divergence.offset.min(self.bytes.len());
+            if offset < self.bytes.len() {
+                write_range(f, &self.bytes[offset..], offset, "<unparsed>")?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,203 @@
+use std::io::{Read, Write};
+use std::process::exit;
+
+use project_kind::{
+    ContainerReader, DecodeLimits, Layout, NumericPolicy, RecordIter, TypeScriptOptions,
+};
+use serde_json::Value;
+
+const USAGE: &str = "usage:
+  kind to-json --schema <layout.json> [--pretty] [--strict | --records] [blob.bin]
+  kind to-json --container [--pretty] [records.kcon]
+  kind to-bin --schema <layout.json> [--numeric checked|wrapping|saturating] [doc.json]
+  kind inspect --schema <layout.json> [blob.bin]
+  kind typescript --schema <layout.json>... [--bigint] [--codec]
+
+Input is read from stdin when no file is given; output goes to stdout.
+--strict rejects bytes after the end of the layout; --records reads a
+stream of concatenated blobs and writes one JSON document per line.
+--container reads a container file, which carries its own layout, and
+writes its records as one JSON array.
+typescript writes an interface per layout, typing u64 and i64 as
+`bigint | number` with --bigint, and bincode decoders/encoders with --codec.";
+
+struct Arguments {
+    command: String,
+    schemas: Vec<String>,
+    pretty: bool,
+    strict: bool,
+    records: bool,
+    container: bool,
+    numeric: NumericPolicy,
+    typescript: TypeScriptOptions,
+    input: Option<String>,
+}
+
+fn parse_arguments(mut args: impl Iterator<Item = String>) -> Result<Arguments, String> {
+    let command = args.next().ok_or("missing command")?;
+    if command == "-h" || command == "--help" {
+        return Err(String::new());
+    }
+    let mut schemas = Vec::new();
+    let mut pretty = false;
+    let mut strict = false;
+    let mut records = false;
+    let mut container = false;
+    let mut numeric = NumericPolicy::Checked;
+    let mut typescript = TypeScriptOptions::default();
+    let mut input = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--schema" => schemas.push(args.next().ok_or("--schema needs a file")?),
+            "--pretty" => pretty = true,
+            "--strict" => strict = true,
+            "--records" => records = true,
+            "--container" => container = true,
+            "--bigint" => typescript.bigint = true,
+            "--codec" => typescript.codec = true,
+            "--numeric" => {
+                numeric = match args.next().as_deref() {
+                    Some("checked") => NumericPolicy::Checked,
+                    Some("wrapping") => NumericPolicy::Wrapping,
+                    Some("saturating") => NumericPolicy::Saturating,
+                    _ => {
+                        return Err(String::from(
+                            "--numeric needs checked, wrapping or saturating",
+                        ))
+                    }
+                }
+            }
+            "-h" | "--help" => return Err(String::new()),
+            _ if arg.starts_with("--") => return Err(format!("unknown option `{}`", arg)),
+            _ if input.is_none() => input = Some(arg),
+            _ => return Err(format!("unexpected argument `{}`", arg)),
+        }
+    }
+    if container {
+        if command != "to-json" || !schemas.is_empty() || strict || records {
+            return Err(String::from(
+                "--container only goes with to-json and --pretty",
+            ));
+        }
+    } else if schemas.is_empty() {
+        return Err(String::from("missing --schema"));
+    }
+    Ok(Arguments {
+        command,
+        schemas,
+        pretty,
+        strict,
+        records,
+        container,
+        numeric,
+        typescript,
+        input,
+    })
+}
+
+fn read_input(input: &Option<String>) -> Result<Vec<u8>, String> {
+    match input {
+        Some(path) => std::fs::read(path).map_err(|error| format!("{}: {}", path, error)),
+        None => {
+            let mut bytes = Vec::new();
+            std::io::stdin()
+                .read_to_end(&mut bytes)
+                .map_err(|error| format!("stdin: {}", error))?;
+            Ok(bytes)
+        }
+    }
+}
+
+fn read_layout(path: &str) -> Result<Layout, String> {
+    let schema = std::fs::read_to_string(path).map_err(|error| format!("{}: {}", path, error))?;
+    let layout =
+        serde_json::from_str::<Layout>(&schema).map_err(|error| format!("{}: {}", path, error))?;
+    layout
+        .validate()
+        .map_err(|error| format!("{}: {}", path, error))?;
+    Ok(layout)
+}
+
+fn run(arguments: Arguments) -> Result<Vec<u8>, String> {
+    if arguments.command == "typescript" {
+        let layouts = arguments
+            .schemas
+            .iter()
+            .map(|schema| read_layout(schema))
+            .collect::<Result<Vec<_>, _>>()?;
+        return Ok(project_kind::to_typescript(&layouts, &arguments.typescript).into_bytes());
+    }
+    if arguments.container {
+        let input = read_input(&arguments.input)?;
+        let value = ContainerReader::new(input.as_slice())
+            .and_then(|reader| reader.to_json())
+            .map_err(|error| error.to_string())?;
+        let mut serial = if arguments.pretty {
+            serde_json::to_string_pretty(&value).unwrap()
+        } else {
+            serde_json::to_string(&value).unwrap()
+        };
+        serial.push('\n');
+        return Ok(serial.into_bytes());
+    }
+    if arguments.schemas.len() > 1 {
+        return Err(format!("`{}` takes a single --schema", arguments.command));
+    }
+    let layout = read_layout(&arguments.schemas[0])?;
+    let input = read_input(&arguments.input)?;
+    match arguments.command.as_str() {
+        "to-json" if arguments.records => {
+            let mut output = Vec::new();
+            for record in RecordIter::new(&layout, &input) {
+                let record = record.map_err(|error| error.to_string())?;
+                serde_json::to_writer(&mut output, &record.value).unwrap();
+                output.push(b'\n');
+            }
+            Ok(output)
+        }
+        "to-json" => {
+            let limits = if arguments.strict {
+                DecodeLimits::strict()
+            } else {
+                DecodeLimits::default()
+            };
+            let mut serial = project_kind::serialize_with_limits(&layout, &input, &limits)
+                .map_err(|error| error.to_string())?;
+            if arguments.pretty {
+                let value = serde_json::from_str::<Value>(&serial).unwrap();
+                serial = serde_json::to_string_pretty(&value).unwrap();
+            }
+            serial.push('\n');
+            Ok(serial.into_bytes())
+        }
+        "to-bin" => {
+            let serial = String::from_utf8(input).map_err(|error| error.to_string())?;
+            project_kind::deserialize_with_policy(&layout, &serial, arguments.numeric)
+                .map_err(|error| error.to_string())
+        }
+        "inspect" => Ok(layout.annotate(&input).to_string().into_bytes()),
+        command => Err(format!("unknown command `{}`", command)),
+    }
+}
+
+fn main() {
+    let arguments = match parse_arguments(std::env::args().skip(1)) {
+        Ok(arguments) => arguments,
+        Err(message) => {
+            if !message.is_empty() {
+                eprintln!("kind: {}", message);
+            }
+            eprintln!("{}", USAGE);
+            exit(2);
+        }
+    };
+    match run(arguments) {
+        Ok(output) => {
+            std::io::stdout().write_all(&output).unwrap();
+        }
+        Err(message) => {
+            eprintln!("kind: {}", message);
+            exit(1);
+        }
+    }
+}
@@ -0,0 +1,196 @@
+use std::fmt;
+
+use serde::de::{self, DeserializeSeed, Deserializer, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use serde::ser::{SerializeMap, SerializeSeq, Serializer};
+use serde::{Deserialize, Serialize};
+
+use crate::{get_in_slice, Layout, LayoutKind};
+
+/// Encoded bytes paired with their layout, serializable into any serde format.
+pub struct LayoutBytes<'a>(pub &'a Layout, pub &'a [u8]);
+
+impl Serialize for LayoutBytes<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let LayoutBytes(layout, bytes) = *self;
+        layout
+            .size_in_bytes(bytes)
+            .map_err(serde::ser::Error::custom)?;
+        ValidBytes(layout, bytes).serialize(serializer)
+    }
+}
+
+// Bytes already checked by `size_in_bytes`, so the walk below cannot go out
+// of bounds or meet an invalid bool or string.
+struct ValidBytes<'a>(&'a Layout, &'a [u8]);
+
+impl Serialize for ValidBytes<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let ValidBytes(layout, bytes) = *self;
+        match layout.kind {
+            LayoutKind::Array => {
+                let element_count_bytes: [u8; 8] = bytes[..8].try_into().unwrap();
+                let element_count = usize::from_le_bytes(element_count_bytes);
+                let element_layout = layout.fields.as_ref().unwrap().first().unwrap();
+                let mut seq = serializer.serialize_seq(Some(element_count))?;
+                let mut offset = 8;
+                for _ in 0..element_count {
+                    seq.serialize_element(&ValidBytes(element_layout, &bytes[offset..]))?;
+                    offset += element_layout.size_in_bytes(&bytes[offset..]).unwrap();
+                }
+                seq.end()
+            }
+            LayoutKind::Struct => {
+                let fields = layout.fields.as_ref().unwrap();
+                let mut map = serializer.serialize_map(Some(fields.len()))?;
+                let mut offset = 0;
+                for field in fields.iter() {
+                    map.serialize_entry(&field.name, &ValidBytes(field, &bytes[offset..]))?;
+                    offset += field.size_in_bytes(&bytes[offset..]).unwrap();
+                }
+                map.end()
+            }
+            LayoutKind::String => {
+                let length_bytes: [u8; 8] = bytes[..8].try_into().unwrap();
+                let length = usize::from_le_bytes(length_bytes);
+                serializer.serialize_str(std::str::from_utf8(&bytes[8..(8 + length)]).unwrap())
+            }
+            LayoutKind::Bool => serializer.serialize_bool(get_in_slice::<bool>(bytes, 0).unwrap()),
+            LayoutKind::U8 => serializer.serialize_u8(get_in_slice::<u8>(bytes, 0).unwrap()),
+            LayoutKind::U16 => serializer.serialize_u16(get_in_slice::<u16>(bytes, 0).unwrap()),
+            LayoutKind::U32 => serializer.serialize_u32(get_in_slice::<u32>(bytes, 0).unwrap()),
+            LayoutKind::U64 => serializer.serialize_u64(get_in_slice::<u64>(bytes, 0).unwrap()),
+            LayoutKind::I8 => serializer.serialize_i8(get_in_slice::<i8>(bytes, 0).unwrap()),
+            LayoutKind::I16 => serializer.serialize_i16(get_in_slice::<i16>(bytes, 0).unwrap()),
+            LayoutKind::I32 => serializer.serialize_i32(get_in_slice::<i32>(bytes, 0).unwrap()),
+            LayoutKind::I64 => serializer.serialize_i64(get_in_slice::<i64>(bytes, 0).unwrap()),
+            LayoutKind::F32 => serializer.serialize_f32(get_in_slice::<f32>(bytes, 0).unwrap()),
+            LayoutKind::F64 => serializer.serialize_f64(get_in_slice::<f64>(bytes, 0).unwrap()),
+        }
+    }
+}
+
+/// Reads a document shaped like `layout` from any serde format and encodes it to bytes.
+pub struct LayoutSeed<'a>(pub &'a Layout);
+
+impl<'de> DeserializeSeed<'de> for LayoutSeed<'_> {
+    type Value = Vec<u8>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let layout = self.0;
+        match layout.kind {
+            LayoutKind::Array => deserializer.deserialize_seq(ArrayVisitor(layout)),
+            LayoutKind::Struct => deserializer.deserialize_map(StructVisitor(layout)),
+            LayoutKind::String => {
+                let value = String::deserialize(deserializer)?;
+                let mut bytes = value.len().to_le_bytes().to_vec();
+                bytes.extend_from_slice(value.as_bytes());
+                Ok(bytes)
+            }
+            LayoutKind::Bool => Ok(vec![bool::deserialize(deserializer)? as u8]),
+            LayoutKind::U8 => Ok(u8::deserialize(deserializer)?.to_le_bytes().to_vec()),
+            LayoutKind::U16 => Ok(u16::deserialize(deserializer)?.to_le_bytes().to_vec()),
+            LayoutKind::U32 => Ok(u32::deserialize(deserializer)?.to_le_bytes().to_vec()),
+            LayoutKind::U64 => Ok(u64::deserialize(deserializer)?.to_le_bytes().to_vec()),
+            LayoutKind::I8 => Ok(i8::deserialize(deserializer)?.to_le_bytes().to_vec()),
+            LayoutKind::I16 => Ok(i16::deserialize(deserializer)?.to_le_bytes().to_vec()),
+            LayoutKind::I32 => Ok(i32::deserialize(deserializer)?.to_le_bytes().to_vec()),
+            LayoutKind::I64 => Ok(i64::deserialize(deserializer)?.to_le_bytes().to_vec()),
+            LayoutKind::F32 => {
+                // As in the JSON encoder, finite values that overflow to
+                // infinity are rejected rather than stored as infinity.
+                let float = f64::deserialize(deserializer)?;
+                let single = float as f32;
+                if float.is_finite() && single.is_infinite() {
+                    return Err(de::Error::custom(format_args!(
+                        "{:e} is out of range for F32 ({:e}..={:e})",
+                        float,
+                        f32::MIN,
+                        f32::MAX
+                    )));
+                }
+                Ok(single.to_le_bytes().to_vec())
+            }
+            LayoutKind::F64 => Ok(f64::deserialize(deserializer)?.to_le_bytes().to_vec()),
+        }
+    }
+}
+
+struct ArrayVisitor<'a>(&'a Layout);
+
+impl<'de> Visitor<'de> for ArrayVisitor<'_> {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "an array for layout `{}`", self.0.name)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Vec<u8>, A::Error> {
+        let element_layout = self.0.fields.as_ref().unwrap().first().unwrap();
+        let mut bytes = vec![0u8; 8];
+        let mut element_count: usize = 0;
+        while let Some(element_bytes) = seq.next_element_seed(LayoutSeed(element_layout))? {
+            bytes.extend_from_slice(&element_bytes);
+            element_count += 1;
+        }
+        bytes[..8].copy_from_slice(&element_count.to_le_bytes());
+        Ok(bytes)
+    }
+
+    fn visit_bytes<E: de::Error>(self, value: &[u8]) -> Result<Vec<u8>, E> {
+        let element_layout = self.0.fields.as_ref().unwrap().first().unwrap();
+        if element_layout.kind != LayoutKind::U8 {
+            return Err(de::Error::invalid_type(de::Unexpected::Bytes(value), &self));
+        }
+        let mut bytes = value.len().to_le_bytes().to_vec();
+        bytes.extend_from_slice(value);
+        Ok(bytes)
+    }
+}
+
+struct StructVisitor<'a>(&'a Layout);
+
+impl<'de> Visitor<'de> for StructVisitor<'_> {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "a map for layout `{}`", self.0.name)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Vec<u8>, A::Error> {
+        let fields = self.0.fields.as_ref().unwrap();
+        let mut field_bytes: Vec<Option<Vec<u8>>> = vec![None; fields.len()];
+        while let Some(key) = map.next_key::<String>()? {
+            match fields.iter().position(|field| field.name == key) {
+                Some(index) => {
+                    if field_bytes[index].is_some() {
+                        return Err(de::Error::custom(format!("duplicate field `{}`", key)));
+                    }
+                    field_bytes[index] = Some(map.next_value_seed(LayoutSeed(&fields[index]))?);
+                }
+                None => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+        let mut bytes = Vec::new();
+        for (field, field_bytes) in fields.iter().zip(field_bytes) {
+            match field_bytes {
+                Some(field_bytes) => bytes.extend_from_slice(&field_bytes),
+                None => return Err(de::Error::custom(format!("missing field `{}`", field.name))),
+            }
+        }
+        Ok(bytes)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Vec<u8>, A::Error> {
+        let fields = self.0.fields.as_ref().unwrap();
+        let mut bytes = Vec::new();
+        for (index, field) in fields.iter().enumerate() {
+            match seq.next_element_seed(LayoutSeed(field))? {
+                Some(field_bytes) => bytes.extend_from_slice(&field_bytes),
+                None => return Err(de::Error::invalid_length(index, &self)),
+            }
+        }
+        Ok(bytes)
+    }
+}
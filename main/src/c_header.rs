@@ -0,0 +1,518 @@
+use std::collections::HashSet;
+
+use crate::path::format_path;
+use crate::{Error, Layout, LayoutKind, PathSegment};
+
+const KEYWORDS: &[&str] = &[
+    "_Alignas",
+    "_Alignof",
+    "_Atomic",
+    "_Bool",
+    "_Complex",
+    "_Generic",
+    "_Imaginary",
+    "_Noreturn",
+    "_Static_assert",
+    "_Thread_local",
+    "alignas",
+    "alignof",
+    "auto",
+    "bool",
+    "break",
+    "case",
+    "char",
+    "const",
+    "constexpr",
+    "continue",
+    "default",
+    "do",
+    "double",
+    "else",
+    "enum",
+    "extern",
+    "false",
+    "float",
+    "for",
+    "goto",
+    "if",
+    "inline",
+    "int",
+    "long",
+    "nullptr",
+    "register",
+    "restrict",
+    "return",
+    "short",
+    "signed",
+    "sizeof",
+    "static",
+    "static_assert",
+    "struct",
+    "switch",
+    "thread_local",
+    "true",
+    "typedef",
+    "typeof",
+    "typeof_unqual",
+    "union",
+    "unsigned",
+    "void",
+    "volatile",
+    "while",
+];
+
+// How many struct levels to look through when bounding array lengths.
+const MIN_SIZE_DEPTH: usize = 16;
+
+const RUNTIME: &str = r#"#ifndef KIND_RUNTIME
+#define KIND_RUNTIME
+
+/* Arrays longer than this are rejected when their elements can be empty. */
+#ifndef KIND_MAX_ARRAY_LENGTH
+#define KIND_MAX_ARRAY_LENGTH (1u << 20)
+#endif
+
+/* UTF-8 bytes inside the decoded buffer; not NUL-terminated. */
+typedef struct {
+    const char *data;
+    uint64_t length;
+} kind_string;
+
+typedef struct {
+    const uint8_t *data;
+    size_t size;
+    size_t offset;
+} kind_reader;
+
+static inline int kind_take(kind_reader *r, size_t length, const uint8_t **bytes) {
+    if (length > r->size - r->offset) {
+        return -1;
+    }
+    *bytes = r->data + r->offset;
+    r->offset += length;
+    return 0;
+}
+
+static inline int kind_read_bits(kind_reader *r, size_t length, uint64_t *out) {
+    const uint8_t *bytes;
+    size_t i;
+    if (kind_take(r, length, &bytes)) {
+        return -1;
+    }
+    *out = 0;
+    for (i = 0; i < length; i++) {
+        *out |= (uint64_t)bytes[i] << (8 * i);
+    }
+    return 0;
+}
+
+#define KIND_READ(name, type, bits_type) \
+    static inline int kind_read_##name(kind_reader *r, type *out) { \
+        uint64_t value; \
+        bits_type bits; \
+        if (kind_read_bits(r, sizeof bits, &value)) { \
+            return -1; \
+        } \
+        bits = (bits_type)value; \
+        memcpy(out, &bits, sizeof bits); \
+        return 0; \
+    }
+
+KIND_READ(u8, uint8_t, uint8_t)
+KIND_READ(u16, uint16_t, uint16_t)
+KIND_READ(u32, uint32_t, uint32_t)
+KIND_READ(u64, uint64_t, uint64_t)
+KIND_READ(i8, int8_t, uint8_t)
+KIND_READ(i16, int16_t, uint16_t)
+KIND_READ(i32, int32_t, uint32_t)
+KIND_READ(i64, int64_t, uint64_t)
+KIND_READ(f32, float, uint32_t)
+KIND_READ(f64, double, uint64_t)
+
+static inline int kind_read_bool(kind_reader *r, bool *out) {
+    uint8_t byte;
+    if (kind_read_u8(r, &byte) || byte > 1) {
+        return -1;
+    }
+    *out = byte == 1;
+    return 0;
+}
+
+/* Reads a length prefix, rejecting counts the remaining bytes cannot hold. */
+static inline int kind_read_length(kind_reader *r, size_t min_element_size, uint64_t *out) {
+    if (kind_read_u64(r, out)) {
+        return -1;
+    }
+    if (min_element_size == 0) {
+        return *out > KIND_MAX_ARRAY_LENGTH ? -1 : 0;
+    }
+    return *out > (r->size - r->offset) / min_element_size ? -1 : 0;
+}
+
+static inline int kind_valid_utf8(const uint8_t *bytes, size_t length) {
+    size_t i = 0;
+    while (i < length) {
+        size_t extra, j;
+        uint32_t min, code;
+        if (bytes[i] < 0x80) {
+            i++;
+            continue;
+        } else if ((bytes[i] & 0xe0) == 0xc0) {
+            extra = 1, min = 0x80, code = bytes[i] & 0x1f;
+        } else if ((bytes[i] & 0xf0) == 0xe0) {
+            extra = 2, min = 0x800, code = bytes[i] & 0x0f;
+        } else if ((bytes[i] & 0xf8) == 0xf0) {
+            extra = 3, min = 0x10000, code = bytes[i] & 0x07;
+        } else {
+            return 0;
+        }
+        if (extra >= length - i) {
+            return 0;
+        }
+        for (j = 1; j <= extra; j++) {
+            if ((bytes[i + j] & 0xc0) != 0x80) {
+                return 0;
+            }
+            code = (code << 6) | (bytes[i + j] & 0x3f);
+        }
+        if (code < min || code > 0x10ffff || (code >= 0xd800 && code <= 0xdfff)) {
+            return 0;
+        }
+        i += extra + 1;
+    }
+    return 1;
+}
+
+static inline int kind_read_string(kind_reader *r, kind_string *out) {
+    const uint8_t *bytes;
+    if (kind_read_length(r, 1, &out->length)) {
+        return -1;
+    }
+    if (kind_take(r, (size_t)out->length, &bytes) || !kind_valid_utf8(bytes, (size_t)out->length)) {
+        return -1;
+    }
+    out->data = (const char *)bytes;
+    return 0;
+}
+
+#endif
+"#;
+
+// Generates a C header with a typedef and a `name_decode` function per
+// layout; the layout names must be C identifiers. Layouts of primitives and
+// structs of them (there are no fixed-length arrays) are fixed-size: their
+// typedefs are packed, so that on little-endian targets they also match the
+// bincode bytes field for field. Other layouts decode into naturally aligned
+// structs whose arrays `name_free` releases.
+pub fn to_c_header(layouts: &[Layout]) -> Result<String, Error> {
+    let mut names = HashSet::new();
+    let mut prefixes = HashSet::new();
+    for layout in layouts.iter() {
+        check_identifier(&layout.name, &[], &mut names)?;
+        check_identifier(&function_prefix(&layout.name), &[], &mut prefixes)?;
+        check_field_names(layout, &mut Vec::new())?;
+    }
+
+    let mut source = String::from(
+        "/* Generated from kind layouts. Do not edit. */\n\
+         #pragma once\n\n\
+         #include <stdbool.h>\n\
+         #include <stddef.h>\n\
+         #include <stdint.h>\n\
+         #include <stdlib.h>\n\
+         #include <string.h>\n\n",
+    );
+    source.push_str(RUNTIME);
+    for layout in layouts.iter() {
+        source.push('\n');
+        source.push_str(&definitions_of(layout));
+    }
+    Ok(source)
+}
+
+fn check_identifier(
+    name: &str,
+    path: &[PathSegment],
+    seen: &mut HashSet<String>,
+) -> Result<(), Error> {
+    let is_identifier = name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    let message = if !is_identifier {
+        format!("`{}` is not a C identifier", name)
+    } else if KEYWORDS.contains(&name) {
+        format!("`{}` is a C keyword", name)
+    } else if !seen.insert(name.to_string()) {
+        format!("duplicate name `{}`", name)
+    } else {
+        return Ok(());
+    };
+    Err(Error::Identifier {
+        path: format_path(path),
+        message,
+    })
+}
+
+fn check_field_names(layout: &Layout, path: &mut Vec<PathSegment>) -> Result<(), Error> {
+    match layout.kind {
+        LayoutKind::Array => {
+            check_field_names(layout.fields.as_ref().unwrap().first().unwrap(), path)?;
+        }
+        LayoutKind::Struct => {
+            let mut seen = HashSet::new();
+            for field in layout.fields.as_ref().unwrap().iter() {
+                path.push(PathSegment::Field(field.name.clone()));
+                check_identifier(&field.name, path, &mut seen)?;
+                check_field_names(field, path)?;
+                path.pop();
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+// Snake case, for function names: `PlayerState` becomes `player_state`.
+fn function_prefix(name: &str) -> String {
+    let mut prefix = String::new();
+    let mut previous_lower = false;
+    for c in name.chars() {
+        if c.is_ascii_uppercase() && previous_lower {
+            prefix.push('_');
+        }
+        previous_lower = c.is_ascii_lowercase() || c.is_ascii_digit();
+        prefix.push(c.to_ascii_lowercase());
+    }
+    prefix
+}
+
+// C forbids empty structs, so a layout containing one is not fixed-size:
+// its typedef gets a placeholder member and no longer matches the bytes.
+fn is_fixed_size(layout: &Layout) -> bool {
+    match layout.kind {
+        LayoutKind::Array | LayoutKind::String => false,
+        LayoutKind::Struct => {
+            let fields = layout.fields.as_ref().unwrap();
+            !fields.is_empty() && fields.iter().all(is_fixed_size)
+        }
+        _ => true,
+    }
+}
+
+fn needs_free(layout: &Layout) -> bool {
+    match layout.kind {
+        LayoutKind::Array => true,
+        LayoutKind::Struct => layout.fields.as_ref().unwrap().iter().any(needs_free),
+        _ => false,
+    }
+}
+
+fn scalar_type(kind: &LayoutKind) -> &'static str {
+    match kind {
+        LayoutKind::Array | LayoutKind::Struct => unreachable!(),
+        LayoutKind::String => "kind_string",
+        LayoutKind::Bool => "bool",
+        LayoutKind::U8 => "uint8_t",
+        LayoutKind::U16 => "uint16_t",
+        LayoutKind::U32 => "uint32_t",
+        LayoutKind::U64 => "uint64_t",
+        LayoutKind::I8 => "int8_t",
+        LayoutKind::I16 => "int16_t",
+        LayoutKind::I32 => "int32_t",
+        LayoutKind::I64 => "int64_t",
+        LayoutKind::F32 => "float",
+        LayoutKind::F64 => "double",
+    }
+}
+
+fn indentation(depth: usize) -> String {
+    "    ".repeat(depth)
+}
+
+// A type specifier for the layout, whose members are indented one level
+// deeper than `depth`.
+fn type_of(layout: &Layout, depth: usize) -> String {
+    let members = match layout.kind {
+        LayoutKind::Array => format!(
+            "{}uint64_t length;\n{}{} *items;\n",
+            indentation(depth + 1),
+            indentation(depth + 1),
+            type_of(layout.fields.as_ref().unwrap().first().unwrap(), depth + 1)
+        ),
+        LayoutKind::Struct => {
+            let fields = layout.fields.as_ref().unwrap();
+            if fields.is_empty() {
+                format!("{}uint8_t unused;\n", indentation(depth + 1))
+            } else {
+                fields
+                    .iter()
+                    .map(|field| {
+                        format!(
+                            "{}{} {};\n",
+                            indentation(depth + 1),
+                            type_of(field, depth + 1),
+                            field.name
+                        )
+                    })
+                    .collect()
+            }
+        }
+        ref kind => return scalar_type(kind).to_string(),
+    };
+    format!("struct {{\n{}{}}}", members, indentation(depth))
+}
+
+fn definitions_of(layout: &Layout) -> String {
+    let name = &layout.name;
+    let prefix = function_prefix(name);
+    let fixed_size = is_fixed_size(layout);
+    let mut source = String::new();
+    if fixed_size {
+        source.push_str("#pragma pack(push, 1)\n");
+    }
+    source.push_str(&format!("typedef {} {};\n", type_of(layout, 0), name));
+    if fixed_size {
+        source.push_str("#pragma pack(pop)\n");
+        source.push_str(&format!(
+            "_Static_assert(sizeof({}) == {}, \"{} must match its bincode size\");\n",
+            name,
+            layout.min_size_in_bytes(usize::MAX),
+            name
+        ));
+    }
+
+    let free = needs_free(layout);
+    if free {
+        source.push_str(&format!(
+            "\n/* Releases the arrays of a value filled in by {}_decode. */\n\
+             static inline void {}_free({} *value) {{\n",
+            prefix, prefix, name
+        ));
+        source.push_str(&free_of(layout, "(*value)", 1));
+        source.push_str("    memset(value, 0, sizeof *value);\n}\n");
+    }
+
+    source.push_str(&format!(
+        "\n/* Decodes a {} from the start of `data`, setting `*read` (unless NULL) to the\n   \
+         number of bytes used. Strings point into `data`. Returns 0, or -1 if the bytes\n   \
+         are malformed. */\n\
+         static inline int {}_decode(const uint8_t *data, size_t size, {} *out, size_t *read) {{\n    \
+         kind_reader r = {{data, size, 0}};\n    \
+         memset(out, 0, sizeof *out);\n",
+        name, prefix, name
+    ));
+    source.push_str(&decode_of(layout, "(*out)", 1));
+    source.push_str("    if (read) {\n        *read = r.offset;\n    }\n    return 0;\nfail:\n");
+    if free {
+        source.push_str(&format!("    {}_free(out);\n", prefix));
+    }
+    source.push_str("    return -1;\n}\n");
+    source
+}
+
+fn member(target: &str, name: &str) -> String {
+    format!("{}.{}", target, name)
+}
+
+// Statements reading the layout into the lvalue `target`; array loop
+// counters are named after `depth`.
+fn decode_of(layout: &Layout, target: &str, depth: usize) -> String {
+    let indent = indentation(depth);
+    match layout.kind {
+        LayoutKind::Array => {
+            let element = layout.fields.as_ref().unwrap().first().unwrap();
+            let length = member(target, "length");
+            let items = member(target, "items");
+            let counter = format!("i{}", depth);
+            format!(
+                "{indent}if (kind_read_length(&r, {min_size}, &{length})) {{\n\
+                 {indent}    goto fail;\n\
+                 {indent}}}\n\
+                 {indent}if ({length}) {{\n\
+                 {indent}    uint64_t {counter};\n\
+                 {indent}    {items} = calloc((size_t){length}, sizeof *{items});\n\
+                 {indent}    if (!{items}) {{\n\
+                 {indent}        goto fail;\n\
+                 {indent}    }}\n\
+                 {indent}    for ({counter} = 0; {counter} < {length}; {counter}++) {{\n\
+                 {body}\
+                 {indent}    }}\n\
+                 {indent}}}\n",
+                indent = indent,
+                min_size = element.min_size_in_bytes(MIN_SIZE_DEPTH),
+                length = length,
+                items = items,
+                counter = counter,
+                body = decode_of(element, &format!("{}[{}]", items, counter), depth + 2),
+            )
+        }
+        LayoutKind::Struct => layout
+            .fields
+            .as_ref()
+            .unwrap()
+            .iter()
+            .map(|field| decode_of(field, &member(target, &field.name), depth))
+            .collect(),
+        LayoutKind::String => read_of("string", target, &indent),
+        LayoutKind::Bool => read_of("bool", target, &indent),
+        LayoutKind::U8 => read_of("u8", target, &indent),
+        LayoutKind::U16 => read_of("u16", target, &indent),
+        LayoutKind::U32 => read_of("u32", target, &indent),
+        LayoutKind::U64 => read_of("u64", target, &indent),
+        LayoutKind::I8 => read_of("i8", target, &indent),
+        LayoutKind::I16 => read_of("i16", target, &indent),
+        LayoutKind::I32 => read_of("i32", target, &indent),
+        LayoutKind::I64 => read_of("i64", target, &indent),
+        LayoutKind::F32 => read_of("f32", target, &indent),
+        LayoutKind::F64 => read_of("f64", target, &indent),
+    }
+}
+
+fn read_of(kind: &str, target: &str, indent: &str) -> String {
+    format!(
+        "{indent}if (kind_read_{kind}(&r, &{target})) {{\n{indent}    goto fail;\n{indent}}}\n",
+        indent = indent,
+        kind = kind,
+        target = target
+    )
+}
+
+// Statements freeing the arrays under the lvalue `target`. Arrays may be
+// partly filled, since decoding stops at the first error, but calloc leaves
+// the rest zeroed.
+fn free_of(layout: &Layout, target: &str, depth: usize) -> String {
+    let indent = indentation(depth);
+    match layout.kind {
+        LayoutKind::Array => {
+            let element = layout.fields.as_ref().unwrap().first().unwrap();
+            let items = member(target, "items");
+            let mut source = String::new();
+            if needs_free(element) {
+                let counter = format!("i{}", depth);
+                source.push_str(&format!(
+                    "{indent}if ({items}) {{\n\
+                     {indent}    uint64_t {counter};\n\
+                     {indent}    for ({counter} = 0; {counter} < {length}; {counter}++) {{\n\
+                     {body}\
+                     {indent}    }}\n\
+                     {indent}}}\n",
+                    indent = indent,
+                    items = items,
+                    counter = counter,
+                    length = member(target, "length"),
+                    body = free_of(element, &format!("{}[{}]", items, counter), depth + 2),
+                ));
+            }
+            source.push_str(&format!("{}free({});\n", indent, items));
+            source
+        }
+        LayoutKind::Struct => layout
+            .fields
+            .as_ref()
+            .unwrap()
+            .iter()
+            .filter(|field| needs_free(field))
+            .map(|field| free_of(field, &member(target, &field.name), depth))
+            .collect(),
+        _ => String::new(),
+    }
+}
@@ -0,0 +1,391 @@
+use crate::{get_in_slice, DecodeLimits, Error, Layout, LayoutKind};
+
+const UNSIGNED: u8 = 0;
+const NEGATIVE: u8 = 1;
+const BYTES: u8 = 2;
+const TEXT: u8 = 3;
+const ARRAY: u8 = 4;
+const MAP: u8 = 5;
+const TAG: u8 = 6;
+const SIMPLE: u8 = 7;
+
+// How deeply an unknown value may nest before skipping it gives up, so that
+// hostile input cannot exhaust the stack. Known fields nest as their layout.
+const MAX_SKIP_DEPTH: usize = 128;
+
+pub fn serialize_cbor(layout: &Layout, bytes: &[u8]) -> Result<Vec<u8>, Error> {
+    serialize_cbor_with_limits(layout, bytes, &DecodeLimits::unlimited())
+}
+
+pub fn serialize_cbor_with_limits(
+    layout: &Layout,
+    bytes: &[u8],
+    limits: &DecodeLimits,
+) -> Result<Vec<u8>, Error> {
+    layout.check_with_limits(bytes, limits)?;
+    let mut serial = Vec::new();
+    write_cbor(layout, bytes, &mut serial);
+    Ok(serial)
+}
+
+pub fn deserialize_cbor(layout: &Layout, serial: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut reader = Reader { serial, offset: 0 };
+    let mut bytes = Vec::new();
+    read_cbor(layout, &mut reader, &mut bytes)?;
+    Ok(bytes)
+}
+
+fn write_header(serial: &mut Vec<u8>, major: u8, argument: u64) {
+    if argument < 24 {
+        serial.push(major << 5 | argument as u8);
+    } else if argument <= u8::MAX as u64 {
+        write_header_with_width(serial, major, argument, 1);
+    } else if argument <= u16::MAX as u64 {
+        write_header_with_width(serial, major, argument, 2);
+    } else if argument <= u32::MAX as u64 {
+        write_header_with_width(serial, major, argument, 4);
+    } else {
+        write_header_with_width(serial, major, argument, 8);
+    }
+}
+
+fn write_header_with_width(serial: &mut Vec<u8>, major: u8, argument: u64, width: usize) {
+    let info = match width {
+        1 => 24,
+        2 => 25,
+        4 => 26,
+        _ => 27,
+    };
+    serial.push(major << 5 | info);
+    serial.extend_from_slice(&argument.to_be_bytes()[(8 - width)..]);
+}
+
+fn write_signed(serial: &mut Vec<u8>, value: i64, width: usize) {
+    if value < 0 {
+        write_header_with_width(serial, NEGATIVE, !value as u64, width);
+    } else {
+        write_header_with_width(serial, UNSIGNED, value as u64, width);
+    }
+}
+
+// Integers always use the argument width of their declared kind instead of the
+// preferred (shortest) serialization, so readers can recover the original type.
+fn write_cbor(layout: &Layout, bytes: &[u8], serial: &mut Vec<u8>) {
+    match layout.kind {
+        LayoutKind::Array => {
+            let element_count_bytes: [u8; 8] = bytes[..8].try_into().unwrap();
+            let element_count = usize::from_le_bytes(element_count_bytes);
+            let element_layout = layout.fields.as_ref().unwrap().first().unwrap();
+            if element_layout.kind == LayoutKind::U8 {
+                write_header(serial, BYTES, element_count as u64);
+                serial.extend_from_slice(&bytes[8..(8 + element_count)]);
+                return;
+            }
+            write_header(serial, ARRAY, element_count as u64);
+            let mut offset = 8;
+            for _ in 0..element_count {
+                write_cbor(element_layout, &bytes[offset..], serial);
+                offset += element_layout.size_in_bytes(&bytes[offset..]).unwrap();
+            }
+        }
+        LayoutKind::Struct => {
+            let fields = layout.fields.as_ref().unwrap();
+            write_header(serial, MAP, fields.len() as u64);
+            let mut offset = 0;
+            for field in fields.iter() {
+                write_header(serial, TEXT, field.name.len() as u64);
+                serial.extend_from_slice(field.name.as_bytes());
+                write_cbor(field, &bytes[offset..], serial);
+                offset += field.size_in_bytes(&bytes[offset..]).unwrap();
+            }
+        }
+        LayoutKind::String => {
+            let length_bytes: [u8; 8] = bytes[..8].try_into().unwrap();
+            let length = usize::from_le_bytes(length_bytes);
+            write_header(serial, TEXT, length as u64);
+            serial.extend_from_slice(&bytes[8..(8 + length)]);
+        }
+        LayoutKind::Bool => {
+            if get_in_slice::<bool>(bytes, 0).unwrap() {
+                serial.push(0xf5);
+            } else {
+                serial.push(0xf4);
+            }
+        }
+        LayoutKind::U8 => {
+            let value = get_in_slice::<u8>(bytes, 0).unwrap();
+            write_header_with_width(serial, UNSIGNED, value as u64, 1)
+        }
+        LayoutKind::U16 => {
+            let value = get_in_slice::<u16>(bytes, 0).unwrap();
+            write_header_with_width(serial, UNSIGNED, value as u64, 2)
+        }
+        LayoutKind::U32 => {
+            let value = get_in_slice::<u32>(bytes, 0).unwrap();
+            write_header_with_width(serial, UNSIGNED, value as u64, 4)
+        }
+        LayoutKind::U64 => {
+            let value = get_in_slice::<u64>(bytes, 0).unwrap();
+            write_header_with_width(serial, UNSIGNED, value, 8)
+        }
+        LayoutKind::I8 => write_signed(serial, get_in_slice::<i8>(bytes, 0).unwrap() as i64, 1),
+        LayoutKind::I16 => write_signed(serial, get_in_slice::<i16>(bytes, 0).unwrap() as i64, 2),
+        LayoutKind::I32 => write_signed(serial, get_in_slice::<i32>(bytes, 0).unwrap() as i64, 4),
+        LayoutKind::I64 => write_signed(serial, get_in_slice::<i64>(bytes, 0).unwrap(), 8),
+        LayoutKind::F32 => {
+            serial.push(0xfa);
+            serial.extend_from_slice(&get_in_slice::<f32>(bytes, 0).unwrap().to_be_bytes());
+        }
+        LayoutKind::F64 => {
+            serial.push(0xfb);
+            serial.extend_from_slice(&get_in_slice::<f64>(bytes, 0).unwrap().to_be_bytes());
+        }
+    }
+}
+
+struct Reader<'a> {
+    serial: &'a [u8],
+    offset: usize,
+}
+
+struct Header {
+    major: u8,
+    info: u8,
+    argument: u64,
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, count: usize) -> Result<&'a [u8], Error> {
+        if count > self.serial.len() - self.offset {
+            return Err(Error::Cbor(format!(
+                "unexpected end of input at byte {}",
+                self.serial.len()
+            )));
+        }
+        let taken = &self.serial[self.offset..(self.offset + count)];
+        self.offset += count;
+        Ok(taken)
+    }
+
+    fn header(&mut self) -> Result<Header, Error> {
+        loop {
+            let offset = self.offset;
+            let initial = self.take(1)?[0];
+            let major = initial >> 5;
+            let info = initial & 0x1f;
+            let argument = match info {
+                0..=23 => info as u64,
+                24 => self.take(1)?[0] as u64,
+                25 => u16::from_be_bytes(self.take(2)?.try_into().unwrap()) as u64,
+                26 => u32::from_be_bytes(self.take(4)?.try_into().unwrap()) as u64,
+                27 => u64::from_be_bytes(self.take(8)?.try_into().unwrap()),
+                _ => {
+                    return Err(Error::Cbor(format!(
+                        "unsupported additional information {} at byte {}",
+                        info, offset
+                    )))
+                }
+            };
+            if major != TAG {
+                return Ok(Header {
+                    major,
+                    info,
+                    argument,
+                    offset,
+                });
+            }
+        }
+    }
+
+    fn skip(&mut self, depth: usize) -> Result<(), Error> {
+        let header = self.header()?;
+        if depth > MAX_SKIP_DEPTH {
+            return Err(Error::Cbor(format!(
+                "value nested deeper than {} levels at byte {}",
+                MAX_SKIP_DEPTH, header.offset
+            )));
+        }
+        match header.major {
+            BYTES | TEXT => {
+                self.take(header.argument as usize)?;
+            }
+            ARRAY => {
+                for _ in 0..header.argument {
+                    self.skip(depth + 1)?;
+                }
+            }
+            MAP => {
+                for _ in 0..header.argument {
+                    self.skip(depth + 1)?;
+                    self.skip(depth + 1)?;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+fn expected(layout: &Layout, expected: &str, header: &Header) -> Error {
+    Error::Cbor(format!(
+        "expected {} for `{}` at byte {}",
+        expected, layout.name, header.offset
+    ))
+}
+
+fn read_integer(layout: &Layout, reader: &mut Reader) -> Result<i128, Error> {
+    let header = reader.header()?;
+    match header.major {
+        UNSIGNED => Ok(header.argument as i128),
+        NEGATIVE => Ok(-1 - header.argument as i128),
+        _ => Err(expected(layout, "an integer", &header)),
+    }
+}
+
+fn read_float(layout: &Layout, reader: &mut Reader) -> Result<f64, Error> {
+    let header = reader.header()?;
+    match (header.major, header.info) {
+        (UNSIGNED, _) => Ok(header.argument as f64),
+        (NEGATIVE, _) => Ok(-1.0 - header.argument as f64),
+        (SIMPLE, 25) => Ok(half_to_f64(header.argument as u16)),
+        (SIMPLE, 26) => Ok(f32::from_bits(header.argument as u32) as f64),
+        (SIMPLE, 27) => Ok(f64::from_bits(header.argument)),
+        _ => Err(expected(layout, "a float", &header)),
+    }
+}
+
+// Decoding of IEEE 754 half precision, as given in RFC 8949 appendix D.
+fn half_to_f64(half: u16) -> f64 {
+    let exponent = (half >> 10) & 0x1f;
+    let mantissa = (half & 0x3ff) as f64;
+    let value = match exponent {
+        0 => mantissa * 2f64.powi(-24),
+        31 if mantissa == 0.0 => f64::INFINITY,
+        31 => f64::NAN,
+        _ => (mantissa + 1024.0) * 2f64.powi(exponent as i32 - 25),
+    };
+    if half & 0x8000 != 0 {
+        -value
+    } else {
+        value
+    }
+}
+
+fn push_integer<T: TryFrom<i128>>(
+    layout: &Layout,
+    reader: &mut Reader,
+    bytes: &mut Vec<u8>,
+    to_bytes: fn(T) -> Vec<u8>,
+) -> Result<(), Error> {
+    let offset = reader.offset;
+    let value = read_integer(layout, reader)?;
+    let value = T::try_from(value).map_err(|_| {
+        Error::Cbor(format!(
+            "integer {} out of range for `{}` ({:?}) at byte {}",
+            value, layout.name, layout.kind, offset
+        ))
+    })?;
+    bytes.extend_from_slice(&to_bytes(value));
+    Ok(())
+}
+
+fn read_cbor(layout: &Layout, reader: &mut Reader, bytes: &mut Vec<u8>) -> Result<(), Error> {
+    match layout.kind {
+        LayoutKind::Array => {
+            let element_layout = layout.fields.as_ref().unwrap().first().unwrap();
+            let header = reader.header()?;
+            match header.major {
+                BYTES if element_layout.kind == LayoutKind::U8 => {
+                    let value = reader.take(header.argument as usize)?;
+                    bytes.extend_from_slice(&value.len().to_le_bytes());
+                    bytes.extend_from_slice(value);
+                }
+                ARRAY => {
+                    bytes.extend_from_slice(&(header.argument as usize).to_le_bytes());
+                    for _ in 0..header.argument {
+                        read_cbor(element_layout, reader, bytes)?;
+                    }
+                }
+                _ => return Err(expected(layout, "an array", &header)),
+            }
+        }
+        LayoutKind::Struct => {
+            let fields = layout.fields.as_ref().unwrap();
+            let header = reader.header()?;
+            if header.major != MAP {
+                return Err(expected(layout, "a map", &header));
+            }
+            let mut field_bytes: Vec<Option<Vec<u8>>> = vec![None; fields.len()];
+            for _ in 0..header.argument {
+                let key_header = reader.header()?;
+                if key_header.major != TEXT {
+                    return Err(expected(layout, "a text key", &key_header));
+                }
+                let key = reader.take(key_header.argument as usize)?;
+                match fields.iter().position(|field| field.name.as_bytes() == key) {
+                    Some(index) => {
+                        if field_bytes[index].is_some() {
+                            return Err(Error::Cbor(format!(
+                                "duplicate field `{}` in map at byte {}",
+                                fields[index].name, key_header.offset
+                            )));
+                        }
+                        let mut value_bytes = Vec::new();
+                        read_cbor(&fields[index], reader, &mut value_bytes)?;
+                        field_bytes[index] = Some(value_bytes);
+                    }
+                    None => reader.skip(0)?,
+                }
+            }
+            for (field, field_bytes) in fields.iter().zip(field_bytes) {
+                match field_bytes {
+                    Some(field_bytes) => bytes.extend_from_slice(&field_bytes),
+                    None => {
+                        return Err(Error::Cbor(format!(
+                            "missing field `{}` in map at byte {}",
+                            field.name, header.offset
+                        )))
+                    }
+                }
+            }
+        }
+        LayoutKind::String => {
+            let header = reader.header()?;
+            if header.major != TEXT {
+                return Err(expected(layout, "text", &header));
+            }
+            let value = reader.take(header.argument as usize)?;
+            if std::str::from_utf8(value).is_err() {
+                return Err(expected(layout, "valid UTF-8 text", &header));
+            }
+            bytes.extend_from_slice(&value.len().to_le_bytes());
+            bytes.extend_from_slice(value);
+        }
+        LayoutKind::Bool => {
+            let header = reader.header()?;
+            match (header.major, header.info) {
+                (SIMPLE, 20) => bytes.push(0),
+                (SIMPLE, 21) => bytes.push(1),
+                _ => return Err(expected(layout, "a bool", &header)),
+            }
+        }
+        LayoutKind::U8 => push_integer(layout, reader, bytes, |v: u8| v.to_le_bytes().to_vec())?,
+        LayoutKind::U16 => push_integer(layout, reader, bytes, |v: u16| v.to_le_bytes().to_vec())?,
+        LayoutKind::U32 => push_integer(layout, reader, bytes, |v: u32| v.to_le_bytes().to_vec())?,
+        LayoutKind::U64 => push_integer(layout, reader, bytes, |v: u64| v.to_le_bytes().to_vec())?,
+        LayoutKind::I8 => push_integer(layout, reader, bytes, |v: i8| v.to_le_bytes().to_vec())?,
+        LayoutKind::I16 => push_integer(layout, reader, bytes, |v: i16| v.to_le_bytes().to_vec())?,
+        LayoutKind::I32 => push_integer(layout, reader, bytes, |v: i32| v.to_le_bytes().to_vec())?,
+        LayoutKind::I64 => push_integer(layout, reader, bytes, |v: i64| v.to_le_bytes().to_vec())?,
+        LayoutKind::F32 => {
+            let value = read_float(layout, reader)? as f32;
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        LayoutKind::F64 => {
+            let value = read_float(layout, reader)?;
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+    }
+    Ok(())
+}
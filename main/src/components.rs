@@ -0,0 +1,33 @@
+use crate::{Error, Layout, Registry};
+
+// What `#[component(register)]` submits for its type. Submissions from every
+// crate linked into the binary are collected at startup.
+pub struct RegisteredComponent {
+    pub name: &'static str,
+    pub get_layout: fn() -> Layout,
+}
+
+inventory::collect!(RegisteredComponent);
+
+// The layout of every registered component in the binary, sorted by name and
+// then fingerprint since link order is unspecified.
+pub fn all_components() -> Vec<Layout> {
+    let mut layouts = inventory::iter::<RegisteredComponent>
+        .into_iter()
+        .map(|component| (component.get_layout)())
+        .collect::<Vec<_>>();
+    layouts.sort_by_cached_key(|layout| (layout.name.clone(), layout.fingerprint()));
+    layouts
+}
+
+impl Registry {
+    // A registry of `all_components()`. Two components with the same name and
+    // shape but different layouts conflict.
+    pub fn from_components() -> Result<Registry, Error> {
+        let mut registry = Registry::new();
+        for layout in all_components() {
+            registry.register_layout(layout)?;
+        }
+        Ok(registry)
+    }
+}
@@ -0,0 +1,194 @@
+use std::io::{Read, Write};
+
+use serde_json::Value;
+
+use crate::{build_value_with_limits, DecodeLimits, Error, Layout};
+
+const MAGIC: &[u8; 4] = b"KCON";
+const VERSION: u8 = 1;
+
+// A container file holds its layout once, followed by records of it:
+//
+//   magic    4 bytes, "KCON"
+//   version  1 byte, currently 1
+//   layout   u64 length, then the layout as JSON
+//   records  u64 length, then the bincode bytes, until the end of the file
+//
+// Integers are little-endian, as in bincode. The layout is stored as JSON so
+// that a container can be read without the types that wrote it.
+pub struct ContainerWriter<W: Write> {
+    writer: W,
+    layout: Layout,
+}
+
+impl<W: Write> ContainerWriter<W> {
+    // Writes the header.
+    pub fn new(mut writer: W, layout: &Layout) -> Result<Self, Error> {
+        let serial = serde_json::to_vec(layout).unwrap();
+        let mut header = Vec::with_capacity(serial.len() + 13);
+        header.extend_from_slice(MAGIC);
+        header.push(VERSION);
+        header.extend_from_slice(&(serial.len() as u64).to_le_bytes());
+        header.extend_from_slice(&serial);
+        writer.write_all(&header).map_err(io_error)?;
+        Ok(ContainerWriter {
+            writer,
+            layout: layout.clone(),
+        })
+    }
+
+    // Appends one record. Fails without writing if `bytes` are not exactly
+    // one value of the layout.
+    pub fn write(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        let end = self.layout.size_in_bytes(bytes)?;
+        if end != bytes.len() {
+            return Err(Error::Container(format!(
+                "{} trailing bytes after the record",
+                bytes.len() - end
+            )));
+        }
+        self.writer
+            .write_all(&(bytes.len() as u64).to_le_bytes())
+            .and_then(|_| self.writer.write_all(bytes))
+            .map_err(io_error)
+    }
+
+    // Flushes and returns the underlying writer.
+    pub fn finish(mut self) -> Result<W, Error> {
+        self.writer.flush().map_err(io_error)?;
+        Ok(self.writer)
+    }
+}
+
+// Reads a container's header, then yields its records' bytes in order. After
+// an error nothing more is yielded.
+pub struct ContainerReader<R: Read> {
+    reader: R,
+    layout: Layout,
+    failed: bool,
+}
+
+impl<R: Read> ContainerReader<R> {
+    pub fn new(mut reader: R) -> Result<Self, Error> {
+        let mut start = [0; 5];
+        read_exact(&mut reader, &mut start, "header")?;
+        if &start[..4] != MAGIC {
+            return Err(Error::Container(String::from("not a container")));
+        }
+        if start[4] != VERSION {
+            return Err(Error::Container(format!(
+                "unsupported container version {}",
+                start[4]
+            )));
+        }
+        let length = read_length(&mut reader, "header")?;
+        let serial = read_bytes(&mut reader, length, "layout")?;
+        let layout = serde_json::from_slice::<Layout>(&serial)
+            .map_err(|error| Error::Container(format!("invalid layout: {}", error)))?;
+        // The layout comes from the file, so check it before decoding with it.
+        layout
+            .validate()
+            .map_err(|error| Error::Container(error.to_string()))?;
+        Ok(ContainerReader {
+            reader,
+            layout,
+            failed: false,
+        })
+    }
+
+    pub fn layout(&self) -> &Layout {
+        &self.layout
+    }
+
+    // Every record as JSON, in one array. Like `write`, rejects records with
+    // bytes after the value.
+    pub fn to_json(self) -> Result<Value, Error> {
+        self.to_json_with_limits(&DecodeLimits::strict())
+    }
+
+    pub fn to_json_with_limits(self, limits: &DecodeLimits) -> Result<Value, Error> {
+        let layout = self.layout.clone();
+        let values = self
+            .map(|bytes| build_value_with_limits(&layout, &bytes?, limits))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Value::Array(values))
+    }
+
+    fn read_record(&mut self) -> Result<Option<Vec<u8>>, Error> {
+        // A clean end of file is only allowed between records.
+        let mut length = [0; 8];
+        let read = read_up_to(&mut self.reader, &mut length)?;
+        if read == 0 {
+            return Ok(None);
+        }
+        if read < length.len() {
+            return Err(truncated("record length"));
+        }
+        let length = u64::from_le_bytes(length);
+        read_bytes(&mut self.reader, length, "record").map(Some)
+    }
+}
+
+impl<R: Read> Iterator for ContainerReader<R> {
+    type Item = Result<Vec<u8>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let record = self.read_record().transpose();
+        if let Some(Err(_)) = record {
+            self.failed = true;
+        }
+        record
+    }
+}
+
+fn io_error(error: std::io::Error) -> Error {
+    Error::Container(error.to_string())
+}
+
+fn truncated(what: &str) -> Error {
+    Error::Container(format!("container ends in a {}", what))
+}
+
+fn read_up_to(reader: &mut impl Read, buffer: &mut [u8]) -> Result<usize, Error> {
+    let mut read = 0;
+    while read < buffer.len() {
+        match reader.read(&mut buffer[read..]) {
+            Ok(0) => break,
+            Ok(count) => read += count,
+            Err(error) if error.kind() == std::io::ErrorKind::Interrupted => {}
+            Err(error) => return Err(io_error(error)),
+        }
+    }
+    Ok(read)
+}
+
+fn read_exact(reader: &mut impl Read, buffer: &mut [u8], what: &str) -> Result<(), Error> {
+    if read_up_to(reader, buffer)? < buffer.len() {
+        return Err(truncated(what));
+    }
+    Ok(())
+}
+
+fn read_length(reader: &mut impl Read, what: &str) -> Result<u64, Error> {
+    let mut length = [0; 8];
+    read_exact(reader, &mut length, what)?;
+    Ok(u64::from_le_bytes(length))
+}
+
+// Reads through `take` rather than allocating `length` up front, so a
+// corrupt length fails at the end of the input instead of exhausting memory.
+fn read_bytes(reader: &mut impl Read, length: u64, what: &str) -> Result<Vec<u8>, Error> {
+    let mut bytes = Vec::new();
+    reader
+        .by_ref()
+        .take(length)
+        .read_to_end(&mut bytes)
+        .map_err(io_error)?;
+    if (bytes.len() as u64) < length {
+        return Err(truncated(what));
+    }
+    Ok(bytes)
+}
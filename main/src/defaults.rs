@@ -0,0 +1,58 @@
+use serde_json::{Map, Value};
+
+use crate::{build_bytes_from_layout, build_value_from_layout, Error, Layout, LayoutKind};
+
+impl Layout {
+    // A layout's own `default` wins; otherwise 0, false, "", empty arrays and
+    // recursively defaulted structs.
+    pub fn default_value(&self) -> Value {
+        if let Some(default) = &self.default {
+            return default.clone();
+        }
+        match self.kind {
+            LayoutKind::Array => Value::from(Vec::<Value>::new()),
+            LayoutKind::Struct => {
+                let mut value = Map::new();
+                for field in self.fields.as_ref().unwrap().iter() {
+                    value.insert(field.name.clone(), field.default_value());
+                }
+                Value::from(value)
+            }
+            LayoutKind::String => Value::from(""),
+            LayoutKind::Bool => Value::from(false),
+            LayoutKind::U8
+            | LayoutKind::U16
+            | LayoutKind::U32
+            | LayoutKind::U64
+            | LayoutKind::I8
+            | LayoutKind::I16
+            | LayoutKind::I32
+            | LayoutKind::I64 => Value::from(0),
+            LayoutKind::F32 | LayoutKind::F64 => Value::from(0.0),
+        }
+    }
+
+    // Fails when a `default` override does not fit its layout.
+    pub fn default_bytes(&self) -> Result<Vec<u8>, Error> {
+        build_bytes_from_layout(self, &self.default_value())
+    }
+
+    pub fn default_json(&self) -> String {
+        serde_json::to_string(&self.default_value()).unwrap()
+    }
+
+    // Sets each field's default override from an encoded instance, as
+    // `#[component(default)]` does with the type's `Default` impl. Fails when
+    // `bytes` do not decode against the layout.
+    pub fn with_field_defaults(mut self, bytes: &[u8]) -> Result<Layout, Error> {
+        let value = build_value_from_layout(&self, bytes)?;
+        if let Some(fields) = self.fields.as_mut() {
+            if self.kind == LayoutKind::Struct {
+                for field in fields.iter_mut() {
+                    field.default = Some(value[&field.name].clone());
+                }
+            }
+        }
+        Ok(self)
+    }
+}
@@ -0,0 +1,204 @@
+use std::fmt;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::path::format_path;
+use crate::{build_value_from_layout, Error, Layout, LayoutKind, PathSegment};
+
+// Paths of removed elements use their index in the old array; modified and
+// inserted elements use their index in the new array.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum Change {
+    Modified {
+        path: String,
+        old: Value,
+        new: Value,
+    },
+    Inserted {
+        path: String,
+        value: Value,
+    },
+    Removed {
+        path: String,
+        value: Value,
+    },
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Change::Modified { path, old, new } => write!(f, "~ {}: {} -> {}", path, old, new),
+            Change::Inserted { path, value } => write!(f, "+ {}: {}", path, value),
+            Change::Removed { path, value } => write!(f, "- {}: {}", path, value),
+        }
+    }
+}
+
+pub fn diff(layout: &Layout, old: &[u8], new: &[u8]) -> Result<Vec<Change>, Error> {
+    let old_value = build_value_from_layout(layout, old)?;
+    let new_value = build_value_from_layout(layout, new)?;
+    let mut changes = Vec::new();
+    diff_values(
+        layout,
+        &mut Vec::new(),
+        &old_value,
+        &new_value,
+        &mut changes,
+    );
+    Ok(changes)
+}
+
+fn diff_values(
+    layout: &Layout,
+    path: &mut Vec<PathSegment>,
+    old: &Value,
+    new: &Value,
+    changes: &mut Vec<Change>,
+) {
+    if old == new {
+        return;
+    }
+    match layout.kind {
+        LayoutKind::Struct => {
+            for field in layout.fields.as_ref().unwrap().iter() {
+                path.push(PathSegment::Field(field.name.clone()));
+                diff_values(field, path, &old[&field.name], &new[&field.name], changes);
+                path.pop();
+            }
+        }
+        LayoutKind::Array => {
+            let element_layout = layout.fields.as_ref().unwrap().first().unwrap();
+            diff_arrays(
+                element_layout,
+                path,
+                old.as_array().unwrap(),
+                new.as_array().unwrap(),
+                changes,
+            );
+        }
+        _ => changes.push(Change::Modified {
+            path: format_path(path),
+            old: old.clone(),
+            new: new.clone(),
+        }),
+    }
+}
+
+// Past this many table cells, finding a longest common subsequence costs more
+// memory than the alignment is worth.
+const MAX_LCS_CELLS: usize = 1 << 20;
+
+// Index pairs of equal elements on a common subsequence of the two arrays,
+// ending with the sentinel pair `(old.len(), new.len())`. The common prefix
+// and suffix always match; between them the subsequence is a longest one if
+// the gap is small enough, and empty otherwise, which pairs the gap's
+// elements by index.
+pub(crate) fn lcs_matches(old: &[Value], new: &[Value]) -> Vec<(usize, usize)> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_gap = &old[prefix..old.len() - suffix];
+    let new_gap = &new[prefix..new.len() - suffix];
+    let mut matches = (0..prefix).map(|i| (i, i)).collect::<Vec<_>>();
+    let cells = (old_gap.len() + 1).saturating_mul(new_gap.len() + 1);
+    if cells <= MAX_LCS_CELLS {
+        for (i, j) in longest_common_subsequence(old_gap, new_gap) {
+            matches.push((prefix + i, prefix + j));
+        }
+    }
+    for k in 0..suffix {
+        matches.push((old.len() - suffix + k, new.len() - suffix + k));
+    }
+    matches.push((old.len(), new.len()));
+    matches
+}
+
+fn longest_common_subsequence(old: &[Value], new: &[Value]) -> Vec<(usize, usize)> {
+    let mut lengths = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lengths[i][j] = if old[i] == new[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+    let mut matches = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            matches.push((i, j));
+            i += 1;
+            j += 1;
+        } else if lengths[i + 1][j] >= lengths[i][j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    matches
+}
+
+// Aligns the arrays on a common subsequence of equal elements (see
+// `lcs_matches`).
+// Within each unaligned gap, elements are paired up and diffed in place and
+// any excess is reported as removed or inserted.
+fn diff_arrays(
+    element_layout: &Layout,
+    path: &mut Vec<PathSegment>,
+    old: &[Value],
+    new: &[Value],
+    changes: &mut Vec<Change>,
+) {
+    let matches = lcs_matches(old, new);
+    let (mut old_start, mut new_start) = (0, 0);
+    for (old_end, new_end) in matches {
+        let paired = (old_end - old_start).min(new_end - new_start);
+        for offset in 0..paired {
+            path.push(PathSegment::Index(new_start + offset));
+            diff_values(
+                element_layout,
+                path,
+                &old[old_start + offset],
+                &new[new_start + offset],
+                changes,
+            );
+            path.pop();
+        }
+        for (index, value) in old
+            .iter()
+            .enumerate()
+            .take(old_end)
+            .skip(old_start + paired)
+        {
+            path.push(PathSegment::Index(index));
+            changes.push(Change::Removed {
+                path: format_path(path),
+                value: value.clone(),
+            });
+            path.pop();
+        }
+        for (index, value) in new
+            .iter()
+            .enumerate()
+            .take(new_end)
+            .skip(new_start + paired)
+        {
+            path.push(PathSegment::Index(index));
+            changes.push(Change::Inserted {
+                path: format_path(path),
+                value: value.clone(),
+            });
+            path.pop();
+        }
+        old_start = old_end + 1;
+        new_start = new_end + 1;
+    }
+}
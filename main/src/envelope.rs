@@ -0,0 +1,167 @@
+use serde_json::Value;
+
+use crate::fingerprint::{hash_bytes, FNV_OFFSET_BASIS};
+use crate::{build_value_with_limits, DecodeLimits, Error, HasLayout, Layout, Registry};
+
+const MAGIC: &[u8; 4] = b"KENV";
+const VERSION: u8 = 1;
+const FLAG_CHECKSUM: u8 = 1;
+
+// A payload tagged with the name and fingerprint of its layout:
+//
+//   magic        4 bytes, "KENV"
+//   version      1 byte, currently 1
+//   flags        1 byte, bit 0 set when a checksum follows the payload
+//   name         u64 length, then UTF-8
+//   fingerprint  u64
+//   payload      u64 length, then the bincode bytes
+//   checksum     u64 FNV-1a of everything before it, if flagged
+//
+// Integers are little-endian, as in bincode.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Envelope<'a> {
+    pub name: &'a str,
+    pub fingerprint: u64,
+    pub payload: &'a [u8],
+    pub checksummed: bool,
+}
+
+impl<'a> Envelope<'a> {
+    // Reads the header and verifies the checksum, if there is one. The
+    // payload is not checked against any layout.
+    pub fn parse(bytes: &'a [u8]) -> Result<Self, Error> {
+        let mut reader = Reader { bytes, offset: 0 };
+        if reader.take(MAGIC.len(), "magic")? != MAGIC {
+            return Err(Error::Envelope(String::from("not an envelope")));
+        }
+        let version = reader.take(1, "version")?[0];
+        if version != VERSION {
+            return Err(Error::Envelope(format!(
+                "unsupported envelope version {}",
+                version
+            )));
+        }
+        let flags = reader.take(1, "flags")?[0];
+        if flags & !FLAG_CHECKSUM != 0 {
+            return Err(Error::Envelope(format!("unknown flags {:#04x}", flags)));
+        }
+        let name_length = reader.length("name")?;
+        let name = std::str::from_utf8(reader.take(name_length, "name")?)
+            .map_err(|_| Error::Envelope(String::from("name is not valid UTF-8")))?;
+        let fingerprint = reader.u64("fingerprint")?;
+        let payload_length = reader.length("payload")?;
+        let payload = reader.take(payload_length, "payload")?;
+        let checksummed = flags & FLAG_CHECKSUM != 0;
+        if checksummed {
+            let expected = checksum(&bytes[..reader.offset]);
+            if reader.u64("checksum")? != expected {
+                return Err(Error::Envelope(String::from("checksum mismatch")));
+            }
+        }
+        if reader.offset != bytes.len() {
+            return Err(Error::Envelope(format!(
+                "{} trailing bytes after the envelope",
+                bytes.len() - reader.offset
+            )));
+        }
+        Ok(Envelope {
+            name,
+            fingerprint,
+            payload,
+            checksummed,
+        })
+    }
+}
+
+// Wraps the bincode bytes of a `T` in a checksummed envelope.
+pub fn wrap<T: HasLayout>(bytes: &[u8]) -> Result<Vec<u8>, Error> {
+    wrap_layout(&T::get_layout(), bytes, true)
+}
+
+// Fails if `bytes` are not exactly one value of the layout.
+pub fn wrap_layout(layout: &Layout, bytes: &[u8], checksummed: bool) -> Result<Vec<u8>, Error> {
+    let end = layout.size_in_bytes(bytes)?;
+    if end != bytes.len() {
+        return Err(Error::Envelope(format!(
+            "{} trailing bytes after the `{}` payload",
+            bytes.len() - end,
+            layout.name
+        )));
+    }
+    let mut envelope = Vec::with_capacity(bytes.len() + layout.name.len() + 38);
+    envelope.extend_from_slice(MAGIC);
+    envelope.push(VERSION);
+    envelope.push(if checksummed { FLAG_CHECKSUM } else { 0 });
+    envelope.extend_from_slice(&(layout.name.len() as u64).to_le_bytes());
+    envelope.extend_from_slice(layout.name.as_bytes());
+    envelope.extend_from_slice(&layout.fingerprint().to_le_bytes());
+    envelope.extend_from_slice(&(bytes.len() as u64).to_le_bytes());
+    envelope.extend_from_slice(bytes);
+    if checksummed {
+        let checksum = checksum(&envelope);
+        envelope.extend_from_slice(&checksum.to_le_bytes());
+    }
+    Ok(envelope)
+}
+
+// Decodes an envelope's payload to JSON with the layout the registry has for
+// its name and fingerprint. The payload must be exactly one value, as `wrap`
+// requires.
+pub fn unwrap_with(registry: &Registry, envelope: &[u8]) -> Result<Value, Error> {
+    unwrap_with_limits(registry, envelope, &DecodeLimits::strict())
+}
+
+// Trailing bytes in the payload are allowed up to `limits.max_trailing_bytes`.
+pub fn unwrap_with_limits(
+    registry: &Registry,
+    envelope: &[u8],
+    limits: &DecodeLimits,
+) -> Result<Value, Error> {
+    let envelope = Envelope::parse(envelope)?;
+    let layout = registry
+        .get(envelope.name, envelope.fingerprint)
+        .ok_or_else(|| {
+            Error::Envelope(format!(
+                "no layout registered as `{}` with fingerprint {:016x}",
+                envelope.name, envelope.fingerprint
+            ))
+        })?;
+    build_value_with_limits(layout, envelope.payload, limits)
+}
+
+fn checksum(bytes: &[u8]) -> u64 {
+    let mut hash = FNV_OFFSET_BASIS;
+    hash_bytes(bytes, &mut hash);
+    hash
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, length: usize, what: &str) -> Result<&'a [u8], Error> {
+        if self.bytes.len() - self.offset < length {
+            return Err(Error::Envelope(format!(
+                "envelope ends in its {} at byte {}",
+                what,
+                self.bytes.len()
+            )));
+        }
+        let slice = &self.bytes[self.offset..self.offset + length];
+        self.offset += length;
+        Ok(slice)
+    }
+
+    fn u64(&mut self, what: &str) -> Result<u64, Error> {
+        Ok(u64::from_le_bytes(self.take(8, what)?.try_into().unwrap()))
+    }
+
+    // Lengths that do not fit a usize cannot fit the input either; `take`
+    // reports them as truncation.
+    fn length(&mut self, what: &str) -> Result<usize, Error> {
+        let length = self.u64(what)?;
+        Ok(usize::try_from(length).unwrap_or(usize::MAX))
+    }
+}
@@ -0,0 +1,146 @@
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Error {
+    Yaml {
+        message: String,
+        line: Option<usize>,
+        column: Option<usize>,
+    },
+    Json(String),
+    Msgpack(String),
+    Cbor(String),
+    Protobuf(String),
+    Csv {
+        message: String,
+        row: Option<usize>,
+        column: Option<usize>,
+    },
+    Path(String),
+    Value {
+        path: String,
+        message: String,
+    },
+    Patch {
+        index: usize,
+        message: String,
+    },
+    Decode {
+        offset: usize,
+        path: String,
+        message: String,
+    },
+    Limit {
+        offset: usize,
+        path: String,
+        limit: String,
+        max: usize,
+    },
+    Identifier {
+        path: String,
+        message: String,
+    },
+    Layout {
+        path: String,
+        message: String,
+    },
+    Conflict {
+        name: String,
+        fingerprint: u64,
+    },
+    Envelope(String),
+    Container(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Yaml {
+                message,
+                line: Some(line),
+                column: Some(column),
+            } => write!(
+                f,
+                "yaml error at line {} column {}: {}",
+                line, column, message
+            ),
+            Error::Yaml { message, .. } => write!(f, "yaml error: {}", message),
+            Error::Json(message) => write!(f, "json error: {}", message),
+            Error::Msgpack(message) => write!(f, "msgpack error: {}", message),
+            Error::Cbor(message) => write!(f, "cbor error: {}", message),
+            Error::Protobuf(message) => write!(f, "protobuf error: {}", message),
+            Error::Csv {
+                message,
+                row: Some(row),
+                column: Some(column),
+            } => write!(f, "csv error at row {} column {}: {}", row, column, message),
+            Error::Csv {
+                message,
+                row: Some(row),
+                column: None,
+            } => write!(f, "csv error at row {}: {}", row, message),
+            Error::Csv { message, .. } => write!(f, "csv error: {}", message),
+            Error::Path(message) => write!(f, "path error: {}", message),
+            Error::Value { path, message } if path.is_empty() => {
+                write!(f, "invalid value: {}", message)
+            }
+            Error::Value { path, message } => {
+                write!(f, "invalid value at `{}`: {}", path, message)
+            }
+            Error::Patch { index, message } => {
+                write!(f, "patch operation {} failed: {}", index, message)
+            }
+            Error::Decode {
+                offset,
+                path,
+                message,
+            } if path.is_empty() => write!(f, "decode error at byte {}: {}", offset, message),
+            Error::Decode {
+                offset,
+                path,
+                message,
+            } => write!(
+                f,
+                "decode error at byte {} in `{}`: {}",
+                offset, path, message
+            ),
+            Error::Limit {
+                offset,
+                path,
+                limit,
+                max,
+            } if path.is_empty() => write!(f, "{} of {} exceeded at byte {}", limit, max, offset),
+            Error::Limit {
+                offset,
+                path,
+                limit,
+                max,
+            } => write!(
+                f,
+                "{} of {} exceeded at byte {} in `{}`",
+                limit, max, offset, path
+            ),
+            Error::Identifier { path, message } if path.is_empty() => {
+                write!(f, "invalid identifier: {}", message)
+            }
+            Error::Identifier { path, message } => {
+                write!(f, "invalid identifier at `{}`: {}", path, message)
+            }
+            Error::Layout { path, message } if path.is_empty() => {
+                write!(f, "invalid layout: {}", message)
+            }
+            Error::Layout { path, message } => {
+                write!(f, "invalid layout at `{}`: {}", path, message)
+            }
+            Error::Conflict { name, fingerprint } => write!(
+                f,
+                "a different layout is already registered as `{}` with fingerprint {:016x}",
+                name, fingerprint
+            ),
+            Error::Envelope(message) => write!(f, "envelope error: {}", message),
+            Error::Container(message) => write!(f, "container error: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
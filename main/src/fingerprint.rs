@@ -0,0 +1,55 @@
+use crate::{Layout, LayoutKind};
+
+pub(crate) const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+impl Layout {
+    // A stable 64-bit FNV-1a hash of the layout's shape: kinds and struct
+    // field names, in order. The layout's own name, array element names and
+    // defaults are left out, so a nested struct hashes like its type.
+    pub fn fingerprint(&self) -> u64 {
+        let mut hash = FNV_OFFSET_BASIS;
+        hash_layout(self, &mut hash);
+        hash
+    }
+}
+
+pub(crate) fn hash_bytes(bytes: &[u8], hash: &mut u64) {
+    for byte in bytes.iter() {
+        *hash ^= *byte as u64;
+        *hash = hash.wrapping_mul(FNV_PRIME);
+    }
+}
+
+fn hash_layout(layout: &Layout, hash: &mut u64) {
+    let tag: u8 = match layout.kind {
+        LayoutKind::Array => 0,
+        LayoutKind::Struct => 1,
+        LayoutKind::String => 2,
+        LayoutKind::Bool => 3,
+        LayoutKind::U8 => 4,
+        LayoutKind::U16 => 5,
+        LayoutKind::U32 => 6,
+        LayoutKind::U64 => 7,
+        LayoutKind::I8 => 8,
+        LayoutKind::I16 => 9,
+        LayoutKind::I32 => 10,
+        LayoutKind::I64 => 11,
+        LayoutKind::F32 => 12,
+        LayoutKind::F64 => 13,
+    };
+    hash_bytes(&[tag], hash);
+    match layout.kind {
+        LayoutKind::Array => hash_layout(layout.fields.as_ref().unwrap().first().unwrap(), hash),
+        LayoutKind::Struct => {
+            let fields = layout.fields.as_ref().unwrap();
+            hash_bytes(&(fields.len() as u64).to_le_bytes(), hash);
+            for field in fields.iter() {
+                hash_bytes(&(field.name.len() as u64).to_le_bytes(), hash);
+                hash_bytes(field.name.as_bytes(), hash);
+                hash_layout(field, hash);
+            }
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,221 @@
+#[cfg(feature = "arbitrary")]
+use arbitrary::{Arbitrary, Unstructured};
+#[cfg(feature = "proptest")]
+use proptest::prelude::*;
+#[cfg(feature = "proptest")]
+use proptest::test_runner::{Config, TestRunner};
+#[cfg(feature = "proptest")]
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{Map, Value};
+
+use crate::{build_bytes_from_layout, Layout, LayoutKind};
+#[cfg(feature = "proptest")]
+use crate::{deserialize, serialize, HasLayout};
+
+// Upper bounds on generated arrays and strings; string lengths count chars.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GenerateLimits {
+    pub max_array_length: usize,
+    pub max_string_length: usize,
+}
+
+impl Default for GenerateLimits {
+    fn default() -> Self {
+        GenerateLimits {
+            max_array_length: 8,
+            max_string_length: 16,
+        }
+    }
+}
+
+fn element_layout(layout: &Layout) -> &Layout {
+    layout.fields.as_ref().unwrap().first().unwrap()
+}
+
+// Floats are kept finite, since JSON has no way to write NaN or infinity.
+#[cfg(feature = "proptest")]
+pub fn value_strategy(layout: &Layout, limits: &GenerateLimits) -> BoxedStrategy<Value> {
+    use proptest::collection::vec;
+    use proptest::num::{f32, f64};
+    match layout.kind {
+        LayoutKind::Array => vec(
+            value_strategy(element_layout(layout), limits),
+            0..=limits.max_array_length,
+        )
+        .prop_map(Value::from)
+        .boxed(),
+        LayoutKind::Struct => {
+            let fields = layout.fields.as_ref().unwrap();
+            let names = fields
+                .iter()
+                .map(|field| field.name.clone())
+                .collect::<Vec<_>>();
+            let strategies = fields
+                .iter()
+                .map(|field| value_strategy(field, limits))
+                .collect::<Vec<_>>();
+            strategies
+                .prop_map(move |values| {
+                    let mut value = Map::new();
+                    for (name, field_value) in names.iter().zip(values) {
+                        value.insert(name.clone(), field_value);
+                    }
+                    Value::from(value)
+                })
+                .boxed()
+        }
+        LayoutKind::String => vec(any::<char>(), 0..=limits.max_string_length)
+            .prop_map(|chars| Value::from(chars.into_iter().collect::<String>()))
+            .boxed(),
+        LayoutKind::Bool => any::<bool>().prop_map(Value::from).boxed(),
+        LayoutKind::U8 => any::<u8>().prop_map(Value::from).boxed(),
+        LayoutKind::U16 => any::<u16>().prop_map(Value::from).boxed(),
+        LayoutKind::U32 => any::<u32>().prop_map(Value::from).boxed(),
+        LayoutKind::U64 => any::<u64>().prop_map(Value::from).boxed(),
+        LayoutKind::I8 => any::<i8>().prop_map(Value::from).boxed(),
+        LayoutKind::I16 => any::<i16>().prop_map(Value::from).boxed(),
+        LayoutKind::I32 => any::<i32>().prop_map(Value::from).boxed(),
+        LayoutKind::I64 => any::<i64>().prop_map(Value::from).boxed(),
+        LayoutKind::F32 => {
+            (f32::POSITIVE | f32::NEGATIVE | f32::ZERO | f32::SUBNORMAL | f32::NORMAL)
+                .prop_map(Value::from)
+                .boxed()
+        }
+        LayoutKind::F64 => {
+            (f64::POSITIVE | f64::NEGATIVE | f64::ZERO | f64::SUBNORMAL | f64::NORMAL)
+                .prop_map(Value::from)
+                .boxed()
+        }
+    }
+}
+
+#[cfg(feature = "proptest")]
+pub fn bytes_strategy(layout: &Layout, limits: &GenerateLimits) -> BoxedStrategy<Vec<u8>> {
+    let layout = layout.clone();
+    value_strategy(&layout, limits)
+        .prop_map(move |value| build_bytes_from_layout(&layout, &value).unwrap())
+        .boxed()
+}
+
+#[cfg(feature = "proptest")]
+pub fn json_strategy(layout: &Layout, limits: &GenerateLimits) -> BoxedStrategy<String> {
+    value_strategy(layout, limits)
+        .prop_map(|value| serde_json::to_string(&value).unwrap())
+        .boxed()
+}
+
+// Checks that random blobs of `T`'s layout decode as `T` and survive
+// `serialize` followed by `deserialize` unchanged. Failures report the
+// shrunk blob.
+#[cfg(feature = "proptest")]
+pub fn check_round_trip<T: HasLayout + Serialize + DeserializeOwned>(
+    limits: &GenerateLimits,
+) -> Result<(), String> {
+    let layout = T::get_layout();
+    let mut runner = TestRunner::new(Config {
+        failure_persistence: None,
+        ..Config::default()
+    });
+    runner
+        .run(&bytes_strategy(&layout, limits), |bytes| {
+            let typed = bincode::deserialize::<T>(&bytes)
+                .map_err(|error| TestCaseError::fail(error.to_string()))?;
+            prop_assert_eq!(&bincode::serialize(&typed).unwrap(), &bytes);
+            prop_assert_eq!(
+                &deserialize(&layout, &serialize(&layout, &bytes).unwrap()).unwrap(),
+                &bytes
+            );
+            Ok(())
+        })
+        .map_err(|error| error.to_string())
+}
+
+// Declares a `#[test]` running `check_round_trip` for a component type.
+#[cfg(feature = "proptest")]
+#[macro_export]
+macro_rules! round_trip_test {
+    ($name:ident, $component:ty) => {
+        #[test]
+        fn $name() {
+            let limits = $crate::GenerateLimits::default();
+            if let Err(message) = $crate::check_round_trip::<$component>(&limits) {
+                panic!("{}", message);
+            }
+        }
+    };
+}
+
+#[cfg(feature = "arbitrary")]
+fn arbitrary_float(u: &mut Unstructured, single: bool) -> arbitrary::Result<Value> {
+    let float = if single {
+        f32::arbitrary(u)? as f64
+    } else {
+        f64::arbitrary(u)?
+    };
+    Ok(Value::from(if float.is_finite() { float } else { 0.0 }))
+}
+
+// Non-finite floats drawn from the input are replaced by zero.
+#[cfg(feature = "arbitrary")]
+pub fn arbitrary_value(
+    layout: &Layout,
+    u: &mut Unstructured,
+    limits: &GenerateLimits,
+) -> arbitrary::Result<Value> {
+    Ok(match layout.kind {
+        LayoutKind::Array => {
+            let element_count = u.int_in_range(0..=limits.max_array_length)?;
+            let mut elements = Vec::with_capacity(element_count);
+            for _ in 0..element_count {
+                elements.push(arbitrary_value(element_layout(layout), u, limits)?);
+            }
+            Value::from(elements)
+        }
+        LayoutKind::Struct => {
+            let mut value = Map::new();
+            for field in layout.fields.as_ref().unwrap().iter() {
+                value.insert(field.name.clone(), arbitrary_value(field, u, limits)?);
+            }
+            Value::from(value)
+        }
+        LayoutKind::String => {
+            let char_count = u.int_in_range(0..=limits.max_string_length)?;
+            let mut string = String::with_capacity(char_count);
+            for _ in 0..char_count {
+                string.push(char::arbitrary(u)?);
+            }
+            Value::from(string)
+        }
+        LayoutKind::Bool => Value::from(bool::arbitrary(u)?),
+        LayoutKind::U8 => Value::from(u8::arbitrary(u)?),
+        LayoutKind::U16 => Value::from(u16::arbitrary(u)?),
+        LayoutKind::U32 => Value::from(u32::arbitrary(u)?),
+        LayoutKind::U64 => Value::from(u64::arbitrary(u)?),
+        LayoutKind::I8 => Value::from(i8::arbitrary(u)?),
+        LayoutKind::I16 => Value::from(i16::arbitrary(u)?),
+        LayoutKind::I32 => Value::from(i32::arbitrary(u)?),
+        LayoutKind::I64 => Value::from(i64::arbitrary(u)?),
+        LayoutKind::F32 => arbitrary_float(u, true)?,
+        LayoutKind::F64 => arbitrary_float(u, false)?,
+    })
+}
+
+#[cfg(feature = "arbitrary")]
+pub fn arbitrary_bytes(
+    layout: &Layout,
+    u: &mut Unstructured,
+    limits: &GenerateLimits,
+) -> arbitrary::Result<Vec<u8>> {
+    let value = arbitrary_value(layout, u, limits)?;
+    Ok(build_bytes_from_layout(layout, &value).unwrap())
+}
+
+#[cfg(feature = "arbitrary")]
+pub fn arbitrary_json(
+    layout: &Layout,
+    u: &mut Unstructured,
+    limits: &GenerateLimits,
+) -> arbitrary::Result<String> {
+    let value = arbitrary_value(layout, u, limits)?;
+    Ok(serde_json::to_string(&value).unwrap())
+}
@@ -0,0 +1,73 @@
+use serde_json::{json, Map, Value};
+
+use crate::numeric::integer_range;
+use crate::{Layout, LayoutKind};
+
+const DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+impl Layout {
+    // A JSON Schema (draft 2020-12) accepting exactly the documents that
+    // `serialize` can produce for this layout.
+    pub fn to_json_schema(&self) -> Value {
+        let mut schema = Map::new();
+        schema.insert(String::from("$schema"), Value::from(DIALECT));
+        schema.insert(String::from("title"), Value::from(self.name.clone()));
+        if let Value::Object(body) = schema_of(self) {
+            schema.extend(body);
+        }
+        Value::from(schema)
+    }
+}
+
+fn schema_of(layout: &Layout) -> Value {
+    let mut schema = match layout.kind {
+        LayoutKind::Array => json!({
+            "type": "array",
+            "items": schema_of(layout.fields.as_ref().unwrap().first().unwrap()),
+        }),
+        LayoutKind::Struct => {
+            let fields = layout.fields.as_ref().unwrap();
+            let mut properties = Map::new();
+            for field in fields.iter() {
+                properties.insert(field.name.clone(), schema_of(field));
+            }
+            let required = fields
+                .iter()
+                .map(|field| Value::from(field.name.clone()))
+                .collect::<Vec<_>>();
+            json!({
+                "type": "object",
+                "properties": properties,
+                "required": required,
+                "additionalProperties": false,
+            })
+        }
+        LayoutKind::String => json!({ "type": "string" }),
+        LayoutKind::Bool => json!({ "type": "boolean" }),
+        LayoutKind::F32 => json!({
+            "type": "number",
+            "minimum": f32::MIN as f64,
+            "maximum": f32::MAX as f64,
+        }),
+        LayoutKind::F64 => json!({ "type": "number" }),
+        ref kind => {
+            let (min, max) = integer_range(kind);
+            json!({
+                "type": "integer",
+                "minimum": integer_value(min),
+                "maximum": integer_value(max),
+            })
+        }
+    };
+    if let Some(default) = &layout.default {
+        schema["default"] = default.clone();
+    }
+    schema
+}
+
+fn integer_value(integer: i128) -> Value {
+    match u64::try_from(integer) {
+        Ok(integer) => Value::from(integer),
+        Err(_) => Value::from(integer as i64),
+    }
+}
@@ -1,15 +1,121 @@
 use std::mem::size_of;
 
+use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
 
-fn get_in_slice<'a, T>(slice: &'a [u8], offset: usize) -> Option<&'a T> {
-    let size = size_of::<T>();
-    if offset + size > slice.len() {
-        return None;
+mod annotate;
+mod bridge;
+mod c_header;
+#[cfg(feature = "cbor")]
+mod cbor;
+#[cfg(feature = "register")]
+mod components;
+mod container;
+mod defaults;
+mod diff;
+mod envelope;
+mod error;
+mod fingerprint;
+#[cfg(any(feature = "arbitrary", feature = "proptest"))]
+mod generate;
+mod json_schema;
+mod limits;
+#[cfg(feature = "msgpack")]
+mod msgpack;
+mod numeric;
+mod patch;
+mod path;
+#[cfg(feature = "protobuf")]
+mod protobuf;
+mod records;
+mod registry;
+mod rust_source;
+#[cfg(feature = "csv")]
+mod tabular;
+mod typescript;
+mod validate;
+#[cfg(feature = "yaml")]
+mod yaml;
+
+#[cfg(feature = "arbitrary")]
+pub use arbitrary;
+pub use bincode;
+#[cfg(feature = "register")]
+pub use inventory;
+#[cfg(feature = "proptest")]
+pub use proptest;
+
+pub use annotate::{AnnotatedRange, Annotation, Divergence};
+pub use bridge::{LayoutBytes, LayoutSeed};
+pub use c_header::to_c_header;
+#[cfg(feature = "cbor")]
+pub use cbor::{deserialize_cbor, serialize_cbor, serialize_cbor_with_limits};
+#[cfg(feature = "register")]
+pub use components::{all_components, RegisteredComponent};
+pub use container::{ContainerReader, ContainerWriter};
+pub use diff::{diff, Change};
+pub use envelope::{unwrap_with, unwrap_with_limits, wrap, wrap_layout, Envelope};
+pub use error::Error;
+#[cfg(any(feature = "arbitrary", feature = "proptest"))]
+pub use generate::GenerateLimits;
+#[cfg(feature = "arbitrary")]
+pub use generate::{arbitrary_bytes, arbitrary_json, arbitrary_value};
+#[cfg(feature = "proptest")]
+pub use generate::{bytes_strategy, check_round_trip, json_strategy, value_strategy};
+pub use limits::DecodeLimits;
+#[cfg(feature = "msgpack")]
+pub use msgpack::{deserialize_msgpack, serialize_msgpack, serialize_msgpack_with_limits};
+pub use numeric::NumericPolicy;
+pub use patch::{apply_json_patch, make_json_patch, Patch, PatchOperation};
+pub use path::{get_path, parse_path, set_path, PathSegment};
+#[cfg(feature = "protobuf")]
+pub use protobuf::{
+    deserialize_protobuf, serialize_protobuf, serialize_protobuf_with_limits, to_proto,
+};
+pub use records::{Record, RecordIter};
+pub use registry::Registry;
+#[cfg(feature = "csv")]
+pub use tabular::{from_csv, to_csv, to_csv_with_limits};
+pub use typescript::{to_typescript, TypeScriptOptions};
+#[cfg(feature = "yaml")]
+pub use yaml::{deserialize_yaml, serialize_yaml, serialize_yaml_with_limits};
+
+// Fixed-width values read from little-endian bytes without any pointer casts.
+// A `bool` only reads from 0 or 1; any other byte reads as `None`.
+trait Primitive: Sized {
+    const SIZE: usize;
+    fn from_le_slice(slice: &[u8]) -> Option<Self>;
+}
+
+macro_rules! impl_primitive {
+    ($($primitive:ty),*) => {
+        $(
+            impl Primitive for $primitive {
+                const SIZE: usize = size_of::<$primitive>();
+                fn from_le_slice(slice: &[u8]) -> Option<Self> {
+                    Some(<$primitive>::from_le_bytes(slice.try_into().ok()?))
+                }
+            }
+        )*
+    };
+}
+
+impl_primitive!(u8, u16, u32, u64, i8, i16, i32, i64, f32, f64);
+
+impl Primitive for bool {
+    const SIZE: usize = 1;
+    fn from_le_slice(slice: &[u8]) -> Option<Self> {
+        match slice {
+            [0] => Some(false),
+            [1] => Some(true),
+            _ => None,
+        }
     }
-    let subslice = &slice[offset..(offset + size)];
-    let value = unsafe { &*(subslice as *const _ as *const T) };
-    Some(value)
+}
+
+fn get_in_slice<T: Primitive>(slice: &[u8], offset: usize) -> Option<T> {
+    let end = offset.checked_add(T::SIZE)?;
+    T::from_le_slice(slice.get(offset..end)?)
 }
 
 pub trait HasLayout {
@@ -17,14 +123,20 @@ pub trait HasLayout {
     fn get_name(&self) -> String;
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Layout {
     pub name: String,
     pub kind: LayoutKind,
     pub fields: Option<Vec<Layout>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<Value>,
+    // The protobuf field number of a struct field, set with
+    // `#[proto(number = ..)]`; declaration order numbers the rest.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub field_number: Option<u32>,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum LayoutKind {
     Array,
     Struct,
@@ -43,165 +155,464 @@ pub enum LayoutKind {
 }
 
 impl Layout {
-    fn size_in_bytes(&self, bytes: &[u8]) -> usize {
+    // Validates `bytes` against the layout and returns how many it spans.
+    // Every length is still checked against the bytes that remain, but large
+    // values are fine; callers with untrusted input use `check_with_limits`.
+    fn size_in_bytes(&self, bytes: &[u8]) -> Result<usize, Error> {
+        self.check_with_limits(bytes, &DecodeLimits::unlimited())
+    }
+
+    fn check_with_limits(&self, bytes: &[u8], limits: &DecodeLimits) -> Result<usize, Error> {
+        let (_, end) = Decoder::new(bytes, limits, false).decode(self, 0)?;
+        check_trailing_bytes(bytes, end, limits)?;
+        Ok(end)
+    }
+
+    fn primitive_size(&self) -> Option<usize> {
+        match self.kind {
+            LayoutKind::Array | LayoutKind::Struct | LayoutKind::String => None,
+            LayoutKind::Bool | LayoutKind::U8 | LayoutKind::I8 => Some(1),
+            LayoutKind::U16 | LayoutKind::I16 => Some(2),
+            LayoutKind::U32 | LayoutKind::I32 | LayoutKind::F32 => Some(4),
+            LayoutKind::U64 | LayoutKind::I64 | LayoutKind::F64 => Some(8),
+        }
+    }
+
+    // The fewest bytes any encoding of the layout can take, counting only
+    // fields within `depth` levels so that deep layouts stay cheap to measure.
+    fn min_size_in_bytes(&self, depth: usize) -> usize {
         match self.kind {
+            LayoutKind::Array | LayoutKind::String => 8,
+            LayoutKind::Struct if depth == 0 => 0,
+            LayoutKind::Struct => self
+                .fields
+                .as_ref()
+                .unwrap()
+                .iter()
+                .map(|field| field.min_size_in_bytes(depth - 1))
+                .sum(),
+            _ => self.primitive_size().unwrap(),
+        }
+    }
+}
+
+// Walks encoded bytes following a layout. Every length prefix is checked
+// against the limits and the bytes that remain before anything is read, so
+// hostile input produces an error instead of a panic or a runaway loop.
+struct Decoder<'a> {
+    bytes: &'a [u8],
+    limits: &'a DecodeLimits,
+    build: bool,
+    path: Vec<PathSegment>,
+    // A lower bound on the size of the JSON output, as decoded so far.
+    output_size: usize,
+}
+
+impl<'a> Decoder<'a> {
+    fn new(bytes: &'a [u8], limits: &'a DecodeLimits, build: bool) -> Self {
+        Decoder {
+            bytes,
+            limits,
+            build,
+            path: Vec::new(),
+            output_size: 0,
+        }
+    }
+
+    fn error(&self, offset: usize, message: String) -> Error {
+        Error::Decode {
+            offset,
+            path: path::format_path(&self.path),
+            message,
+        }
+    }
+
+    fn limit_error(&self, offset: usize, limit: &str, max: usize) -> Error {
+        Error::Limit {
+            offset,
+            path: path::format_path(&self.path),
+            limit: limit.to_string(),
+            max,
+        }
+    }
+
+    fn check_limit(
+        &self,
+        offset: usize,
+        limit: &str,
+        value: usize,
+        max: usize,
+    ) -> Result<(), Error> {
+        if value > max {
+            return Err(self.limit_error(offset, limit, max));
+        }
+        Ok(())
+    }
+
+    fn add_output(&mut self, offset: usize, size: usize) -> Result<(), Error> {
+        self.output_size = self.output_size.saturating_add(size);
+        self.check_limit(
+            offset,
+            "max_output_size",
+            self.output_size,
+            self.limits.max_output_size,
+        )
+    }
+
+    fn read_slice(&self, offset: usize, length: usize) -> Result<&'a [u8], Error> {
+        let remaining = self.bytes.len().saturating_sub(offset);
+        if length > remaining {
+            return Err(self.error(
+                offset,
+                format!("truncated: needs {} bytes, {} remain", length, remaining),
+            ));
+        }
+        Ok(&self.bytes[offset..(offset + length)])
+    }
+
+    fn read_length(&self, offset: usize) -> Result<usize, Error> {
+        let length = get_in_slice::<u64>(self.read_slice(offset, 8)?, 0).unwrap();
+        usize::try_from(length)
+            .map_err(|_| self.error(offset, format!("length {} does not fit", length)))
+    }
+
+    fn read_primitive<T: Primitive>(&self, offset: usize) -> Result<T, Error> {
+        self.read_slice(offset, T::SIZE)?;
+        get_in_slice::<T>(self.bytes, offset).ok_or_else(|| {
+            self.error(
+                offset,
+                format!("invalid bool byte {:#04x}", self.bytes[offset]),
+            )
+        })
+    }
+
+    // Decodes the value at `offset` and returns it with the offset where it
+    // ends. The value is `Null` for containers when `build` is not set.
+    fn decode(&mut self, layout: &Layout, offset: usize) -> Result<(Value, usize), Error> {
+        self.check_limit(offset, "max_depth", self.path.len(), self.limits.max_depth)?;
+        let value = match layout.kind {
             LayoutKind::Array => {
-                let element_layout = self.fields.as_ref().unwrap().first().unwrap();
-                let element_count_bytes: [u8; 8] = bytes[..8].try_into().unwrap();
-                let element_count = usize::from_le_bytes(element_count_bytes);
-                let mut byte_count = 0;
-                for _ in 0..element_count {
-                    let size = element_layout.size_in_bytes(&bytes[(8 + byte_count)..]);
-                    byte_count = byte_count + size;
+                let element_count = self.read_length(offset)?;
+                self.check_limit(
+                    offset,
+                    "max_array_length",
+                    element_count,
+                    self.limits.max_array_length,
+                )?;
+                let element_layout = layout.fields.as_ref().unwrap().first().unwrap();
+                let remaining = self.bytes.len() - offset - 8;
+                let min_size = element_layout
+                    .min_size_in_bytes(self.limits.max_depth.saturating_sub(self.path.len()));
+                if min_size > 0 && element_count > remaining / min_size {
+                    return Err(self.error(
+                        offset,
+                        format!(
+                            "{} elements cannot fit in the {} bytes that remain",
+                            element_count, remaining
+                        ),
+                    ));
                 }
-                byte_count + 8
+                self.add_output(offset, 2 + element_count.saturating_sub(1))?;
+                let mut end = offset + 8;
+                let mut value_array = Vec::new();
+                for index in 0..element_count {
+                    self.path.push(PathSegment::Index(index));
+                    let (element_value, element_end) = self.decode(element_layout, end)?;
+                    self.path.pop();
+                    if self.build {
+                        value_array.push(element_value);
+                    }
+                    end = element_end;
+                }
+                return Ok((Value::from(value_array), end));
             }
             LayoutKind::Struct => {
-                let mut byte_count = 0;
-                for field in self.fields.as_ref().unwrap().iter() {
-                    byte_count = byte_count + field.size_in_bytes(&bytes[byte_count..]);
+                let fields = layout.fields.as_ref().unwrap();
+                let keys_size = fields
+                    .iter()
+                    .map(|field| field.name.len() + 3)
+                    .sum::<usize>();
+                self.add_output(offset, 2 + keys_size + fields.len().saturating_sub(1))?;
+                let mut value = Map::new();
+                let mut end = offset;
+                for field in fields.iter() {
+                    self.path.push(PathSegment::Field(field.name.clone()));
+                    let (field_value, field_end) = self.decode(field, end)?;
+                    self.path.pop();
+                    if self.build {
+                        value.insert(field.name.clone(), field_value);
+                    }
+                    end = field_end;
                 }
-                byte_count
+                return Ok((Value::from(value), end));
             }
             LayoutKind::String => {
-                let length_bytes: [u8; 8] = bytes[..8].try_into().unwrap();
-                let length = usize::from_le_bytes(length_bytes);
-                8 + length
+                let length = self.read_length(offset)?;
+                self.check_limit(
+                    offset,
+                    "max_string_bytes",
+                    length,
+                    self.limits.max_string_bytes,
+                )?;
+                self.add_output(offset, length.saturating_add(2))?;
+                let string_bytes = self.read_slice(offset + 8, length)?;
+                let value = std::str::from_utf8(string_bytes).map_err(|error| {
+                    self.error(
+                        offset + 8 + error.valid_up_to(),
+                        String::from("invalid utf-8 in string"),
+                    )
+                })?;
+                return Ok((Value::from(value), offset + 8 + length));
             }
-            LayoutKind::Bool => 1,
-            LayoutKind::U8 => 1,
-            LayoutKind::U16 => 2,
-            LayoutKind::U32 => 4,
-            LayoutKind::U64 => 8,
-            LayoutKind::I8 => 1,
-            LayoutKind::I16 => 2,
-            LayoutKind::I32 => 4,
-            LayoutKind::I64 => 8,
-            LayoutKind::F32 => 4,
-            LayoutKind::F64 => 8,
-        }
+            LayoutKind::Bool => Value::from(self.read_primitive::<bool>(offset)?),
+            LayoutKind::U8 => Value::from(self.read_primitive::<u8>(offset)?),
+            LayoutKind::U16 => Value::from(self.read_primitive::<u16>(offset)?),
+            LayoutKind::U32 => Value::from(self.read_primitive::<u32>(offset)?),
+            LayoutKind::U64 => Value::from(self.read_primitive::<u64>(offset)?),
+            LayoutKind::I8 => Value::from(self.read_primitive::<i8>(offset)?),
+            LayoutKind::I16 => Value::from(self.read_primitive::<i16>(offset)?),
+            LayoutKind::I32 => Value::from(self.read_primitive::<i32>(offset)?),
+            LayoutKind::I64 => Value::from(self.read_primitive::<i64>(offset)?),
+            LayoutKind::F32 => Value::from(self.read_primitive::<f32>(offset)?),
+            LayoutKind::F64 => Value::from(self.read_primitive::<f64>(offset)?),
+        };
+        self.add_output(offset, 1)?;
+        Ok((value, offset + layout.primitive_size().unwrap()))
     }
 }
 
-fn build_value_from_layout(layout: &Layout, bytes: &[u8]) -> Value {
-    match layout.kind {
-        LayoutKind::Array => {
-            let element_count_bytes: [u8; 8] = bytes[..8].try_into().unwrap();
-            let element_count = usize::from_le_bytes(element_count_bytes);
-            let start = 8;
-            let element_layout = layout.fields.as_ref().unwrap().first().unwrap();
-            let mut offset = start;
-            let mut value_array = Vec::new();
-            for _ in 0..element_count {
-                let element_value = build_value_from_layout(&element_layout, &bytes[offset..]);
-                value_array.push(element_value);
-                let element_size = element_layout.size_in_bytes(&bytes[offset..]);
-                offset = offset + element_size;
-            }
-            Value::from(value_array)
-        }
-        LayoutKind::Struct => {
-            let mut value = Map::new();
-            let mut offset = 0;
-            for field in layout.fields.as_ref().unwrap().iter() {
-                value.insert(
-                    field.name.clone(),
-                    build_value_from_layout(&field, &bytes[offset..]),
-                );
-                offset = offset + field.size_in_bytes(&bytes[offset..]);
-            }
-            Value::from(value)
-        }
-        LayoutKind::String => {
-            let length_bytes: [u8; 8] = bytes[..8].try_into().unwrap();
-            let length = usize::from_le_bytes(length_bytes);
-            let start = 8;
-            let end = start + length;
-            Value::from(String::from_utf8(bytes[start..end].to_vec()).unwrap())
+fn build_value_from_layout(layout: &Layout, bytes: &[u8]) -> Result<Value, Error> {
+    build_value_with_limits(layout, bytes, &DecodeLimits::unlimited())
+}
+
+fn build_value_with_limits(
+    layout: &Layout,
+    bytes: &[u8],
+    limits: &DecodeLimits,
+) -> Result<Value, Error> {
+    let (value, end) = Decoder::new(bytes, limits, true).decode(layout, 0)?;
+    check_trailing_bytes(bytes, end, limits)?;
+    Ok(value)
+}
+
+fn check_trailing_bytes(bytes: &[u8], end: usize, limits: &DecodeLimits) -> Result<(), Error> {
+    if bytes.len() - end > limits.max_trailing_bytes {
+        return Err(Error::Limit {
+            offset: end,
+            path: String::new(),
+            limit: String::from("max_trailing_bytes"),
+            max: limits.max_trailing_bytes,
+        });
+    }
+    Ok(())
+}
+
+// Encodes a JSON value following a layout, reporting values of the wrong type
+// or out of range for their kind with the path where they were found.
+struct Encoder {
+    policy: NumericPolicy,
+    path: Vec<PathSegment>,
+}
+
+impl Encoder {
+    fn error(&self, message: String) -> Error {
+        Error::Value {
+            path: path::format_path(&self.path),
+            message,
         }
-        LayoutKind::Bool => Value::from(*get_in_slice::<bool>(&bytes, 0).unwrap()),
-        LayoutKind::U8 => Value::from(*get_in_slice::<u8>(&bytes, 0).unwrap()),
-        LayoutKind::U16 => Value::from(*get_in_slice::<u16>(&bytes, 0).unwrap()),
-        LayoutKind::U32 => Value::from(*get_in_slice::<u32>(&bytes, 0).unwrap()),
-        LayoutKind::U64 => Value::from(*get_in_slice::<u64>(&bytes, 0).unwrap()),
-        LayoutKind::I8 => Value::from(*get_in_slice::<i8>(&bytes, 0).unwrap()),
-        LayoutKind::I16 => Value::from(*get_in_slice::<i16>(&bytes, 0).unwrap()),
-        LayoutKind::I32 => Value::from(*get_in_slice::<i32>(&bytes, 0).unwrap()),
-        LayoutKind::I64 => Value::from(*get_in_slice::<i64>(&bytes, 0).unwrap()),
-        LayoutKind::F32 => Value::from(*get_in_slice::<f32>(&bytes, 0).unwrap()),
-        LayoutKind::F64 => Value::from(*get_in_slice::<f64>(&bytes, 0).unwrap()),
-    }
-}
-
-fn build_bytes_from_layout(layout: &Layout, value: &Value) -> Vec<u8> {
-    match layout.kind {
-        LayoutKind::Array => {
-            let value_array = value.as_array().unwrap();
-            let mut bytes = Vec::new();
-            let length_bytes = value_array.len().to_le_bytes();
-            for byte in length_bytes.iter() {
-                bytes.push(*byte);
-            }
-            let element_layout = layout.fields.as_ref().unwrap().first().unwrap();
-            for element in value_array.iter() {
-                let element_bytes = build_bytes_from_layout(&element_layout, element);
-                for byte in element_bytes.iter() {
-                    bytes.push(*byte);
+    }
+
+    fn expected(&self, expected: &str, value: &Value) -> Error {
+        self.error(format!("expected {}, found {}", expected, value))
+    }
+
+    fn encode(&mut self, layout: &Layout, value: &Value, bytes: &mut Vec<u8>) -> Result<(), Error> {
+        match layout.kind {
+            LayoutKind::Array => {
+                let value_array = value
+                    .as_array()
+                    .ok_or_else(|| self.expected("an array", value))?;
+                bytes.extend_from_slice(&(value_array.len() as u64).to_le_bytes());
+                let element_layout = layout.fields.as_ref().unwrap().first().unwrap();
+                for (index, element) in value_array.iter().enumerate() {
+                    self.path.push(PathSegment::Index(index));
+                    self.encode(element_layout, element, bytes)?;
+                    self.path.pop();
                 }
             }
-            bytes
-        }
-        LayoutKind::Struct => {
-            let mut bytes = Vec::new();
-            let value_object = value.as_object().unwrap();
-            for field in layout.fields.as_ref().unwrap().iter() {
-                let field_value = value_object.get(&field.name).unwrap();
-                let field_bytes = build_bytes_from_layout(&field, field_value);
-                for byte in field_bytes.iter() {
-                    bytes.push(*byte);
+            LayoutKind::Struct => {
+                let value_object = value
+                    .as_object()
+                    .ok_or_else(|| self.expected("an object", value))?;
+                for field in layout.fields.as_ref().unwrap().iter() {
+                    self.path.push(PathSegment::Field(field.name.clone()));
+                    let field_value = value_object
+                        .get(&field.name)
+                        .ok_or_else(|| self.error(String::from("missing field")))?;
+                    self.encode(field, field_value, bytes)?;
+                    self.path.pop();
                 }
             }
-            bytes
-        }
-        LayoutKind::String => {
-            let value_string = value.as_str().unwrap();
-            let mut bytes = Vec::new();
-            let length_bytes = value_string.len().to_le_bytes();
-            for byte in length_bytes.iter() {
-                bytes.push(*byte);
+            LayoutKind::String => {
+                let value_string = value
+                    .as_str()
+                    .ok_or_else(|| self.expected("a string", value))?;
+                bytes.extend_from_slice(&(value_string.len() as u64).to_le_bytes());
+                bytes.extend_from_slice(value_string.as_bytes());
+            }
+            LayoutKind::Bool => {
+                let value_bool = value
+                    .as_bool()
+                    .ok_or_else(|| self.expected("a bool", value))?;
+                bytes.push(value_bool as u8);
+            }
+            LayoutKind::F32 => {
+                let float = value
+                    .as_f64()
+                    .ok_or_else(|| self.expected("a number", value))?;
+                let single = match self.policy {
+                    // Values just past f32::MAX still round to it; only reject
+                    // those that overflow to infinity.
+                    NumericPolicy::Checked if (float as f32).is_infinite() => {
+                        return Err(self.error(format!(
+                            "{} is out of range for F32 ({:e}..={:e})",
+                            value,
+                            f32::MIN,
+                            f32::MAX
+                        )))
+                    }
+                    NumericPolicy::Saturating => {
+                        float.clamp(f32::MIN as f64, f32::MAX as f64) as f32
+                    }
+                    _ => float as f32,
+                };
+                bytes.extend_from_slice(&single.to_le_bytes());
             }
-            let string_bytes = value_string.as_bytes();
-            for byte in string_bytes.iter() {
-                bytes.push(*byte);
+            LayoutKind::F64 => {
+                let float = value
+                    .as_f64()
+                    .ok_or_else(|| self.expected("a number", value))?;
+                bytes.extend_from_slice(&float.to_le_bytes());
+            }
+            ref kind => {
+                let integer = value
+                    .as_i64()
+                    .map(i128::from)
+                    .or_else(|| value.as_u64().map(i128::from))
+                    .ok_or_else(|| self.expected("an integer", value))?;
+                let (min, max) = numeric::integer_range(kind);
+                let integer = match self.policy {
+                    NumericPolicy::Checked if integer < min || integer > max => {
+                        return Err(self.error(format!(
+                            "{} is out of range for {:?} ({}..={})",
+                            integer, kind, min, max
+                        )))
+                    }
+                    NumericPolicy::Saturating => integer.clamp(min, max),
+                    _ => integer,
+                };
+                bytes.extend_from_slice(&numeric::integer_bytes(kind, integer));
             }
-            bytes
         }
-        LayoutKind::Bool => {
-            if value.as_bool().expect("Could not read value as bool") {
-                vec![1u8]
-            } else {
-                vec![0u8]
+        Ok(())
+    }
+}
+
+fn build_bytes_from_layout(layout: &Layout, value: &Value) -> Result<Vec<u8>, Error> {
+    build_bytes_with_policy(layout, value, NumericPolicy::Checked)
+}
+
+fn build_bytes_with_policy(
+    layout: &Layout,
+    value: &Value,
+    policy: NumericPolicy,
+) -> Result<Vec<u8>, Error> {
+    let mut encoder = Encoder {
+        policy,
+        path: Vec::new(),
+    };
+    let mut bytes = Vec::new();
+    encoder.encode(layout, value, &mut bytes)?;
+    Ok(bytes)
+}
+
+// Fields present in `partial` override `base`; nested structs merge field by
+// field while arrays and primitives are replaced whole. Keys that name no
+// field are rejected, so a misspelled override does not silently do nothing.
+fn merge_value_from_layout(
+    layout: &Layout,
+    base: &mut Value,
+    partial: &Value,
+    path: &mut Vec<PathSegment>,
+) -> Result<(), Error> {
+    match (&layout.kind, partial) {
+        (LayoutKind::Struct, Value::Object(partial_object)) => {
+            let fields = layout.fields.as_ref().unwrap();
+            for key in partial_object.keys() {
+                if !fields.iter().any(|field| &field.name == key) {
+                    path.push(PathSegment::Field(key.clone()));
+                    return Err(Error::Value {
+                        path: path::format_path(path),
+                        message: format!("unknown field `{}`", key),
+                    });
+                }
+            }
+            let base_object = base.as_object_mut().unwrap();
+            for field in fields.iter() {
+                if let Some(field_partial) = partial_object.get(&field.name) {
+                    let field_base = base_object.get_mut(&field.name).unwrap();
+                    path.push(PathSegment::Field(field.name.clone()));
+                    merge_value_from_layout(field, field_base, field_partial, path)?;
+                    path.pop();
+                }
             }
         }
-        LayoutKind::U8 => (value.as_u64().unwrap() as u8).to_be_bytes().to_vec(),
-        LayoutKind::U16 => (value.as_u64().unwrap() as u16).to_le_bytes().to_vec(),
-        LayoutKind::U32 => (value.as_u64().unwrap() as u32).to_le_bytes().to_vec(),
-        LayoutKind::U64 => (value.as_u64().unwrap() as u64).to_le_bytes().to_vec(),
-        LayoutKind::I8 => (value.as_i64().unwrap() as i8).to_be_bytes().to_vec(),
-        LayoutKind::I16 => (value.as_i64().unwrap() as i16).to_le_bytes().to_vec(),
-        LayoutKind::I32 => (value.as_i64().unwrap() as i32).to_le_bytes().to_vec(),
-        LayoutKind::I64 => (value.as_i64().unwrap() as i64).to_le_bytes().to_vec(),
-        LayoutKind::F32 => (value.as_f64().unwrap() as f32).to_le_bytes().to_vec(),
-        LayoutKind::F64 => (value.as_f64().unwrap() as f64).to_le_bytes().to_vec(),
+        _ => *base = partial.clone(),
     }
+    Ok(())
+}
+
+pub fn serialize(layout: &Layout, bytes: &[u8]) -> Result<String, Error> {
+    serialize_with_limits(layout, bytes, &DecodeLimits::unlimited())
+}
+
+pub fn serialize_with_limits(
+    layout: &Layout,
+    bytes: &[u8],
+    limits: &DecodeLimits,
+) -> Result<String, Error> {
+    let value = build_value_with_limits(layout, bytes, limits)?;
+    let serial = serde_json::to_string(&value).unwrap();
+    if serial.len() > limits.max_output_size {
+        return Err(Error::Limit {
+            offset: bytes.len(),
+            path: String::new(),
+            limit: String::from("max_output_size"),
+            max: limits.max_output_size,
+        });
+    }
+    Ok(serial)
+}
+
+fn parse_json(serial: &str) -> Result<Value, Error> {
+    serde_json::from_str::<Value>(serial).map_err(|error| Error::Json(error.to_string()))
+}
+
+pub fn deserialize(layout: &Layout, serial: &str) -> Result<Vec<u8>, Error> {
+    deserialize_with_policy(layout, serial, NumericPolicy::Checked)
 }
 
-pub fn serialize(layout: &Layout, bytes: &Vec<u8>) -> String {
-    let value = build_value_from_layout(layout, &bytes);
-    serde_json::to_string(&value).unwrap()
+pub fn deserialize_with_policy(
+    layout: &Layout,
+    serial: &str,
+    policy: NumericPolicy,
+) -> Result<Vec<u8>, Error> {
+    build_bytes_with_policy(layout, &parse_json(serial)?, policy)
 }
 
-pub fn deserialize(layout: &Layout, serial: &String) -> Vec<u8> {
-    let value = serde_json::from_str::<Value>(serial).unwrap();
+pub fn deserialize_merge(layout: &Layout, base: &[u8], serial: &str) -> Result<Vec<u8>, Error> {
+    let partial = parse_json(serial)?;
+    let mut value = build_value_from_layout(layout, base)?;
+    merge_value_from_layout(layout, &mut value, &partial, &mut Vec::new())?;
     build_bytes_from_layout(layout, &value)
 }
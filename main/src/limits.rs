@@ -0,0 +1,48 @@
+// Bounds on what decoding a blob may cost, checked before the work is done so
+// that a blob from an untrusted client cannot claim more than it is allowed.
+// Functions without limits in their name decode with `unlimited()`, where
+// lengths are still checked against the input but large values are fine.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DecodeLimits {
+    // Arrays and structs enclosing a value; the top level is depth 0.
+    pub max_depth: usize,
+    pub max_array_length: usize,
+    pub max_string_bytes: usize,
+    // Size of the produced JSON document in bytes.
+    pub max_output_size: usize,
+    // Bytes allowed after the end of the layout; 0 rejects any garbage.
+    pub max_trailing_bytes: usize,
+}
+
+impl DecodeLimits {
+    pub fn unlimited() -> Self {
+        DecodeLimits {
+            max_depth: usize::MAX,
+            max_array_length: usize::MAX,
+            max_string_bytes: usize::MAX,
+            max_output_size: usize::MAX,
+            max_trailing_bytes: usize::MAX,
+        }
+    }
+
+    // The default limits, without any trailing bytes.
+    pub fn strict() -> Self {
+        DecodeLimits {
+            max_trailing_bytes: 0,
+            ..DecodeLimits::default()
+        }
+    }
+}
+
+// Sized for untrusted input rather than for the largest valid blob.
+impl Default for DecodeLimits {
+    fn default() -> Self {
+        DecodeLimits {
+            max_depth: 64,
+            max_array_length: 1 << 20,
+            max_string_bytes: 1 << 24,
+            max_output_size: 1 << 28,
+            max_trailing_bytes: usize::MAX,
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use rmp::encode;
+use serde::de::DeserializeSeed;
+
+use crate::{get_in_slice, DecodeLimits, Error, Layout, LayoutKind, LayoutSeed};
+
+pub fn serialize_msgpack(layout: &Layout, bytes: &[u8]) -> Result<Vec<u8>, Error> {
+    serialize_msgpack_with_limits(layout, bytes, &DecodeLimits::unlimited())
+}
+
+pub fn serialize_msgpack_with_limits(
+    layout: &Layout,
+    bytes: &[u8],
+    limits: &DecodeLimits,
+) -> Result<Vec<u8>, Error> {
+    layout.check_with_limits(bytes, limits)?;
+    let mut serial = Vec::new();
+    write_msgpack(layout, bytes, &mut serial);
+    Ok(serial)
+}
+
+pub fn deserialize_msgpack(layout: &Layout, serial: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut deserializer = rmp_serde::Deserializer::from_read_ref(serial);
+    LayoutSeed(layout)
+        .deserialize(&mut deserializer)
+        .map_err(|error| Error::Msgpack(error.to_string()))
+}
+
+// Integers are written with the marker of their declared width (uint8, int16, ...)
+// rather than the most compact one, so readers can recover the original type.
+fn write_msgpack(layout: &Layout, bytes: &[u8], serial: &mut Vec<u8>) {
+    match layout.kind {
+        LayoutKind::Array => {
+            let element_count_bytes: [u8; 8] = bytes[..8].try_into().unwrap();
+            let element_count = usize::from_le_bytes(element_count_bytes);
+            let element_layout = layout.fields.as_ref().unwrap().first().unwrap();
+            if element_layout.kind == LayoutKind::U8 {
+                encode::write_bin(serial, &bytes[8..(8 + element_count)]).unwrap();
+                return;
+            }
+            encode::write_array_len(serial, element_count as u32).unwrap();
+            let mut offset = 8;
+            for _ in 0..element_count {
+                write_msgpack(element_layout, &bytes[offset..], serial);
+                offset += element_layout.size_in_bytes(&bytes[offset..]).unwrap();
+            }
+        }
+        LayoutKind::Struct => {
+            let fields = layout.fields.as_ref().unwrap();
+            encode::write_map_len(serial, fields.len() as u32).unwrap();
+            let mut offset = 0;
+            for field in fields.iter() {
+                encode::write_str(serial, &field.name).unwrap();
+                write_msgpack(field, &bytes[offset..], serial);
+                offset += field.size_in_bytes(&bytes[offset..]).unwrap();
+            }
+        }
+        LayoutKind::String => {
+            let length_bytes: [u8; 8] = bytes[..8].try_into().unwrap();
+            let length = usize::from_le_bytes(length_bytes);
+            let value = std::str::from_utf8(&bytes[8..(8 + length)]).unwrap();
+            encode::write_str(serial, value).unwrap();
+        }
+        LayoutKind::Bool => {
+            encode::write_bool(serial, get_in_slice::<bool>(bytes, 0).unwrap()).unwrap()
+        }
+        LayoutKind::U8 => encode::write_u8(serial, get_in_slice::<u8>(bytes, 0).unwrap()).unwrap(),
+        LayoutKind::U16 => {
+            encode::write_u16(serial, get_in_slice::<u16>(bytes, 0).unwrap()).unwrap()
+        }
+        LayoutKind::U32 => {
+            encode::write_u32(serial, get_in_slice::<u32>(bytes, 0).unwrap()).unwrap()
+        }
+        LayoutKind::U64 => {
+            encode::write_u64(serial, get_in_slice::<u64>(bytes, 0).unwrap()).unwrap()
+        }
+        LayoutKind::I8 => encode::write_i8(serial, get_in_slice::<i8>(bytes, 0).unwrap()).unwrap(),
+        LayoutKind::I16 => {
+            encode::write_i16(serial, get_in_slice::<i16>(bytes, 0).unwrap()).unwrap()
+        }
+        LayoutKind::I32 => {
+            encode::write_i32(serial, get_in_slice::<i32>(bytes, 0).unwrap()).unwrap()
+        }
+        LayoutKind::I64 => {
+            encode::write_i64(serial, get_in_slice::<i64>(bytes, 0).unwrap()).unwrap()
+        }
+        LayoutKind::F32 => {
+            encode::write_f32(serial, get_in_slice::<f32>(bytes, 0).unwrap()).unwrap()
+        }
+        LayoutKind::F64 => {
+            encode::write_f64(serial, get_in_slice::<f64>(bytes, 0).unwrap()).unwrap()
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use crate::LayoutKind;
+
+// How JSON numbers that do not fit their layout kind are encoded. `Checked`
+// rejects them; `Wrapping` keeps the old `as` casts, truncating integers and
+// letting large floats become infinite; `Saturating` clamps to the nearest
+// representable value.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum NumericPolicy {
+    #[default]
+    Checked,
+    Wrapping,
+    Saturating,
+}
+
+pub(crate) fn integer_range(kind: &LayoutKind) -> (i128, i128) {
+    match kind {
+        LayoutKind::U8 => (0, u8::MAX as i128),
+        LayoutKind::U16 => (0, u16::MAX as i128),
+        LayoutKind::U32 => (0, u32::MAX as i128),
+        LayoutKind::U64 => (0, u64::MAX as i128),
+        LayoutKind::I8 => (i8::MIN as i128, i8::MAX as i128),
+        LayoutKind::I16 => (i16::MIN as i128, i16::MAX as i128),
+        LayoutKind::I32 => (i32::MIN as i128, i32::MAX as i128),
+        LayoutKind::I64 => (i64::MIN as i128, i64::MAX as i128),
+        kind => unreachable!("{:?} is not an integer kind", kind),
+    }
+}
+
+// Truncates like an `as` cast, which is the identity for values in range.
+pub(crate) fn integer_bytes(kind: &LayoutKind, integer: i128) -> Vec<u8> {
+    match kind {
+        LayoutKind::U8 => (integer as u8).to_le_bytes().to_vec(),
+        LayoutKind::U16 => (integer as u16).to_le_bytes().to_vec(),
+        LayoutKind::U32 => (integer as u32).to_le_bytes().to_vec(),
+        LayoutKind::U64 => (integer as u64).to_le_bytes().to_vec(),
+        LayoutKind::I8 => (integer as i8).to_le_bytes().to_vec(),
+        LayoutKind::I16 => (integer as i16).to_le_bytes().to_vec(),
+        LayoutKind::I32 => (integer as i32).to_le_bytes().to_vec(),
+        LayoutKind::I64 => (integer as i64).to_le_bytes().to_vec(),
+        kind => unreachable!("{:?} is not an integer kind", kind),
+    }
+}
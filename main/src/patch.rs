@@ -0,0 +1,315 @@
+use serde::de::DeserializeSeed;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::diff::lcs_matches;
+use crate::{build_value_from_layout, Error, Layout, LayoutKind, LayoutSeed};
+
+// An RFC 6902 JSON Patch document.
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Patch(pub Vec<PatchOperation>);
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum PatchOperation {
+    Add { path: String, value: Value },
+    Remove { path: String },
+    Replace { path: String, value: Value },
+    Move { from: String, path: String },
+    Copy { from: String, path: String },
+    Test { path: String, value: Value },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Step {
+    Field(String),
+    Index(usize),
+    Append,
+}
+
+fn parse_pointer(pointer: &str) -> Result<Vec<String>, String> {
+    if pointer.is_empty() {
+        return Ok(Vec::new());
+    }
+    let rest = pointer
+        .strip_prefix('/')
+        .ok_or_else(|| format!("pointer `{}` does not start with `/`", pointer))?;
+    Ok(rest
+        .split('/')
+        .map(|token| token.replace("~1", "/").replace("~0", "~"))
+        .collect())
+}
+
+fn escape_token(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+// Resolves a pointer against the layout, checking that every struct member
+// exists and every array token is an index, before any value is touched.
+fn resolve<'a>(
+    layout: &'a Layout,
+    pointer: &str,
+    allow_append: bool,
+) -> Result<(Vec<Step>, &'a Layout), String> {
+    let tokens = parse_pointer(pointer)?;
+    let mut layout = layout;
+    let mut steps = Vec::new();
+    for (depth, token) in tokens.iter().enumerate() {
+        match layout.kind {
+            LayoutKind::Struct => {
+                layout = layout
+                    .fields
+                    .as_ref()
+                    .unwrap()
+                    .iter()
+                    .find(|field| &field.name == token)
+                    .ok_or_else(|| format!("no field `{}` in `{}`", token, layout.name))?;
+                steps.push(Step::Field(token.clone()));
+            }
+            LayoutKind::Array => {
+                if token == "-" && allow_append && depth == tokens.len() - 1 {
+                    steps.push(Step::Append);
+                } else if token == "0" || (!token.starts_with('0') && !token.starts_with('+')) {
+                    let index = token
+                        .parse::<usize>()
+                        .map_err(|_| format!("invalid array index `{}`", token))?;
+                    steps.push(Step::Index(index));
+                } else {
+                    return Err(format!("invalid array index `{}`", token));
+                }
+                layout = layout.fields.as_ref().unwrap().first().unwrap();
+            }
+            _ => {
+                return Err(format!(
+                    "`{}` is {:?} and has no member `{}`",
+                    layout.name, layout.kind, token
+                ))
+            }
+        }
+    }
+    Ok((steps, layout))
+}
+
+fn value_at_mut<'v>(value: &'v mut Value, steps: &[Step]) -> Result<&'v mut Value, String> {
+    let mut value = value;
+    for step in steps.iter() {
+        value = match step {
+            Step::Field(name) => value.get_mut(name.as_str()).unwrap(),
+            Step::Index(index) => {
+                let array = value.as_array_mut().unwrap();
+                let length = array.len();
+                array
+                    .get_mut(*index)
+                    .ok_or_else(|| format!("index {} out of bounds for length {}", index, length))?
+            }
+            Step::Append => return Err(String::from("`-` does not refer to an element")),
+        };
+    }
+    Ok(value)
+}
+
+fn check_value(layout: &Layout, value: &Value) -> Result<(), String> {
+    LayoutSeed(layout)
+        .deserialize(value)
+        .map(|_| ())
+        .map_err(|error| error.to_string())
+}
+
+fn get(layout: &Layout, document: &mut Value, pointer: &str) -> Result<Value, String> {
+    let (steps, _) = resolve(layout, pointer, false)?;
+    Ok(value_at_mut(document, &steps)?.clone())
+}
+
+fn add(layout: &Layout, document: &mut Value, pointer: &str, value: Value) -> Result<(), String> {
+    let (steps, target_layout) = resolve(layout, pointer, true)?;
+    check_value(target_layout, &value)?;
+    let (last, parent_steps) = match steps.split_last() {
+        Some(split) => split,
+        None => {
+            *document = value;
+            return Ok(());
+        }
+    };
+    let parent = value_at_mut(document, parent_steps)?;
+    match last {
+        Step::Field(name) => {
+            parent[name.as_str()] = value;
+        }
+        Step::Index(index) => {
+            let array = parent.as_array_mut().unwrap();
+            if *index > array.len() {
+                return Err(format!(
+                    "index {} out of bounds for length {}",
+                    index,
+                    array.len()
+                ));
+            }
+            array.insert(*index, value);
+        }
+        Step::Append => parent.as_array_mut().unwrap().push(value),
+    }
+    Ok(())
+}
+
+fn remove(layout: &Layout, document: &mut Value, pointer: &str) -> Result<Value, String> {
+    let (steps, _) = resolve(layout, pointer, false)?;
+    match steps.split_last() {
+        Some((Step::Index(index), parent_steps)) => {
+            let array = value_at_mut(document, parent_steps)?
+                .as_array_mut()
+                .unwrap();
+            if *index >= array.len() {
+                return Err(format!(
+                    "index {} out of bounds for length {}",
+                    index,
+                    array.len()
+                ));
+            }
+            Ok(array.remove(*index))
+        }
+        Some((Step::Field(name), _)) => Err(format!("cannot remove struct field `{}`", name)),
+        _ => Err(String::from("cannot remove the whole document")),
+    }
+}
+
+fn replace(
+    layout: &Layout,
+    document: &mut Value,
+    pointer: &str,
+    value: Value,
+) -> Result<(), String> {
+    let (steps, target_layout) = resolve(layout, pointer, false)?;
+    check_value(target_layout, &value)?;
+    *value_at_mut(document, &steps)? = value;
+    Ok(())
+}
+
+fn apply_operation(
+    layout: &Layout,
+    document: &mut Value,
+    operation: &PatchOperation,
+) -> Result<(), String> {
+    match operation {
+        PatchOperation::Add { path, value } => add(layout, document, path, value.clone()),
+        PatchOperation::Remove { path } => remove(layout, document, path).map(|_| ()),
+        PatchOperation::Replace { path, value } => replace(layout, document, path, value.clone()),
+        PatchOperation::Move { from, path } => {
+            if path.starts_with(&format!("{}/", from)) {
+                return Err(format!("cannot move `{}` into its own child", from));
+            }
+            let value = remove(layout, document, from)?;
+            add(layout, document, path, value)
+        }
+        PatchOperation::Copy { from, path } => {
+            let value = get(layout, document, from)?;
+            add(layout, document, path, value)
+        }
+        PatchOperation::Test { path, value } => {
+            // Compared as encoded by the target layout, so that numbers match
+            // by value: `1` tests equal to an F32 holding `1.0`.
+            let (steps, target_layout) = resolve(layout, path, false)?;
+            let current = value_at_mut(document, &steps)?;
+            let current_bytes = LayoutSeed(target_layout).deserialize(&*current).ok();
+            let expected_bytes = LayoutSeed(target_layout).deserialize(value).ok();
+            if current_bytes.is_some() && current_bytes == expected_bytes {
+                Ok(())
+            } else {
+                Err(format!("value at `{}` does not match", path))
+            }
+        }
+    }
+}
+
+// Operations are applied to a decoded copy of the blob and only encoded back
+// once all of them succeed, so a failing patch leaves nothing half-applied.
+pub fn apply_json_patch(layout: &Layout, bytes: &[u8], patch: &Patch) -> Result<Vec<u8>, Error> {
+    let mut document = build_value_from_layout(layout, bytes)?;
+    for (index, operation) in patch.0.iter().enumerate() {
+        apply_operation(layout, &mut document, operation)
+            .map_err(|message| Error::Patch { index, message })?;
+    }
+    LayoutSeed(layout)
+        .deserialize(&document)
+        .map_err(|error| Error::Patch {
+            index: patch.0.len(),
+            message: error.to_string(),
+        })
+}
+
+pub fn make_json_patch(layout: &Layout, old: &[u8], new: &[u8]) -> Result<Patch, Error> {
+    let old_value = build_value_from_layout(layout, old)?;
+    let new_value = build_value_from_layout(layout, new)?;
+    let mut operations = Vec::new();
+    make_operations(layout, "", &old_value, &new_value, &mut operations);
+    Ok(Patch(operations))
+}
+
+fn make_operations(
+    layout: &Layout,
+    pointer: &str,
+    old: &Value,
+    new: &Value,
+    operations: &mut Vec<PatchOperation>,
+) {
+    if old == new {
+        return;
+    }
+    match layout.kind {
+        LayoutKind::Struct => {
+            for field in layout.fields.as_ref().unwrap().iter() {
+                let field_pointer = format!("{}/{}", pointer, escape_token(&field.name));
+                make_operations(
+                    field,
+                    &field_pointer,
+                    &old[&field.name],
+                    &new[&field.name],
+                    operations,
+                );
+            }
+        }
+        LayoutKind::Array => {
+            let element_layout = layout.fields.as_ref().unwrap().first().unwrap();
+            let old = old.as_array().unwrap();
+            let new = new.as_array().unwrap();
+            // `lcs_matches` only aligns gaps small enough to be cheap; larger
+            // ones are replaced element by element.
+            // `position` tracks the index in the array as it is being patched,
+            // so the operations are valid when applied in order.
+            let mut position = 0;
+            let (mut old_start, mut new_start) = (0, 0);
+            for (old_end, new_end) in lcs_matches(old, new) {
+                let paired = (old_end - old_start).min(new_end - new_start);
+                for offset in 0..paired {
+                    make_operations(
+                        element_layout,
+                        &format!("{}/{}", pointer, position),
+                        &old[old_start + offset],
+                        &new[new_start + offset],
+                        operations,
+                    );
+                    position += 1;
+                }
+                for _ in (old_start + paired)..old_end {
+                    operations.push(PatchOperation::Remove {
+                        path: format!("{}/{}", pointer, position),
+                    });
+                }
+                for value in new.iter().take(new_end).skip(new_start + paired) {
+                    operations.push(PatchOperation::Add {
+                        path: format!("{}/{}", pointer, position),
+                        value: value.clone(),
+                    });
+                    position += 1;
+                }
+                position += 1;
+                old_start = old_end + 1;
+                new_start = new_end + 1;
+            }
+        }
+        _ => operations.push(PatchOperation::Replace {
+            path: pointer.to_string(),
+            value: new.clone(),
+        }),
+    }
+}
@@ -0,0 +1,157 @@
+use serde::de::DeserializeSeed;
+use serde_json::Value;
+
+use crate::{build_value_from_layout, Error, Layout, LayoutKind, LayoutSeed};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PathSegment {
+    Field(String),
+    Index(usize),
+}
+
+// Paths look like `inventory[3].name`; an empty path is the whole blob.
+pub fn parse_path(path: &str) -> Result<Vec<PathSegment>, Error> {
+    let mut segments = Vec::new();
+    let mut rest = path;
+    while !rest.is_empty() {
+        if let Some(after_bracket) = rest.strip_prefix('[') {
+            let close = after_bracket
+                .find(']')
+                .ok_or_else(|| Error::Path(format!("unclosed `[` in `{}`", path)))?;
+            let index = after_bracket[..close]
+                .parse::<usize>()
+                .map_err(|_| Error::Path(format!("invalid index in `{}`", path)))?;
+            segments.push(PathSegment::Index(index));
+            rest = &after_bracket[(close + 1)..];
+        } else {
+            if !segments.is_empty() {
+                rest = rest
+                    .strip_prefix('.')
+                    .ok_or_else(|| Error::Path(format!("expected `.` or `[` in `{}`", path)))?;
+            }
+            let end = rest.find(['.', '[']).unwrap_or(rest.len());
+            if end == 0 {
+                return Err(Error::Path(format!("empty field name in `{}`", path)));
+            }
+            segments.push(PathSegment::Field(rest[..end].to_string()));
+            rest = &rest[end..];
+        }
+    }
+    Ok(segments)
+}
+
+pub(crate) fn format_path(segments: &[PathSegment]) -> String {
+    let mut path = String::new();
+    for segment in segments.iter() {
+        match segment {
+            PathSegment::Field(name) if path.is_empty() => path.push_str(name),
+            PathSegment::Field(name) => {
+                path.push('.');
+                path.push_str(name);
+            }
+            PathSegment::Index(index) => path.push_str(&format!("[{}]", index)),
+        }
+    }
+    path
+}
+
+// The layout found at a path and the byte range it occupies in the blob.
+pub(crate) struct Located<'a> {
+    pub layout: &'a Layout,
+    pub start: usize,
+    pub end: usize,
+}
+
+pub(crate) fn locate<'a>(
+    layout: &'a Layout,
+    bytes: &[u8],
+    segments: &[PathSegment],
+) -> Result<Located<'a>, Error> {
+    layout.size_in_bytes(bytes)?;
+    let mut layout = layout;
+    let mut offset = 0;
+    for (depth, segment) in segments.iter().enumerate() {
+        match (segment, &layout.kind) {
+            (PathSegment::Field(name), LayoutKind::Struct) => {
+                let mut found = None;
+                for field in layout.fields.as_ref().unwrap().iter() {
+                    if &field.name == name {
+                        found = Some(field);
+                        break;
+                    }
+                    offset += field.size_in_bytes(&bytes[offset..]).unwrap();
+                }
+                layout = found.ok_or_else(|| {
+                    Error::Path(format!(
+                        "no field `{}` in `{}` at `{}`",
+                        name,
+                        layout.name,
+                        format_path(&segments[..depth])
+                    ))
+                })?;
+            }
+            (PathSegment::Index(index), LayoutKind::Array) => {
+                let element_count_bytes: [u8; 8] = bytes[offset..(offset + 8)].try_into().unwrap();
+                let element_count = usize::from_le_bytes(element_count_bytes);
+                if *index >= element_count {
+                    return Err(Error::Path(format!(
+                        "index {} out of bounds for `{}` of length {}",
+                        index,
+                        format_path(&segments[..depth]),
+                        element_count
+                    )));
+                }
+                let element_layout = layout.fields.as_ref().unwrap().first().unwrap();
+                offset += 8;
+                for _ in 0..*index {
+                    offset += element_layout.size_in_bytes(&bytes[offset..]).unwrap();
+                }
+                layout = element_layout;
+            }
+            (segment, kind) => {
+                let expected = match segment {
+                    PathSegment::Field(_) => "a struct",
+                    PathSegment::Index(_) => "an array",
+                };
+                return Err(Error::Path(format!(
+                    "`{}` is {:?}, not {}",
+                    format_path(&segments[..depth]),
+                    kind,
+                    expected
+                )));
+            }
+        }
+    }
+    let end = offset + layout.size_in_bytes(&bytes[offset..]).unwrap();
+    Ok(Located {
+        layout,
+        start: offset,
+        end,
+    })
+}
+
+pub fn get_path(layout: &Layout, bytes: &[u8], path: &str) -> Result<Value, Error> {
+    let segments = parse_path(path)?;
+    let located = locate(layout, bytes, &segments)?;
+    build_value_from_layout(located.layout, &bytes[located.start..located.end])
+}
+
+// Re-encodes the value at `path` and splices it in, so strings and arrays may
+// change size; their own length prefixes are rewritten by the re-encoding.
+pub fn set_path(
+    layout: &Layout,
+    bytes: &mut Vec<u8>,
+    path: &str,
+    value: Value,
+) -> Result<(), Error> {
+    let segments = parse_path(path)?;
+    let located = locate(layout, bytes, &segments)?;
+    let value_bytes = LayoutSeed(located.layout)
+        .deserialize(value)
+        .map_err(|error| Error::Value {
+            path: path.to_string(),
+            message: error.to_string(),
+        })?;
+    bytes.splice(located.start..located.end, value_bytes);
+    Ok(())
+}
@@ -0,0 +1,564 @@
+use std::collections::HashSet;
+
+use crate::numeric::{integer_bytes, integer_range};
+use crate::path::format_path;
+use crate::rust_source::type_name;
+use crate::{DecodeLimits, Error, Layout, LayoutKind, PathSegment};
+
+const VARINT: u64 = 0;
+const FIXED64: u64 = 1;
+const LENGTH_DELIMITED: u64 = 2;
+const FIXED32: u64 = 5;
+
+const MAX_FIELD_NUMBER: u32 = (1 << 29) - 1;
+const RESERVED_FIELD_NUMBERS: std::ops::RangeInclusive<u32> = 19000..=19999;
+
+// Generates a proto3 file with a message per layout; the layouts must be
+// structs. Nested structs become nested messages named after their field,
+// and an array of arrays repeats a message holding the inner array as
+// `repeated .. items = 1`. Field numbers are the fields' `field_number`s,
+// or their position (counting from 1) when not set.
+pub fn to_proto(layouts: &[Layout]) -> Result<String, Error> {
+    let mut source = String::from("// Generated from kind layouts. Do not edit.\n");
+    source.push_str("syntax = \"proto3\";\n");
+    let mut names = HashSet::new();
+    for layout in layouts.iter() {
+        check_identifier(&layout.name, &[], &mut names)?;
+        check_message(layout, &mut Vec::new())?;
+        source.push('\n');
+        source.push_str(&message_of(layout, &layout.name, 0));
+    }
+    Ok(source)
+}
+
+// Converts bincode bytes of a struct layout to a protobuf message. Zero
+// values are left out, as proto3 writers do, except that a float of -0.0 is
+// written so that it survives the round trip.
+pub fn serialize_protobuf(layout: &Layout, bytes: &[u8]) -> Result<Vec<u8>, Error> {
+    serialize_protobuf_with_limits(layout, bytes, &DecodeLimits::unlimited())
+}
+
+pub fn serialize_protobuf_with_limits(
+    layout: &Layout,
+    bytes: &[u8],
+    limits: &DecodeLimits,
+) -> Result<Vec<u8>, Error> {
+    check_message(layout, &mut Vec::new())?;
+    layout.check_with_limits(bytes, limits)?;
+    let mut serial = Vec::new();
+    write_message(layout, bytes, &mut serial);
+    Ok(serial)
+}
+
+// Converts a protobuf message to bincode bytes. Missing fields take their
+// zero value, unknown fields are skipped, repeated primitives may be packed
+// or not, and a repeated occurrence of a message field merges into it.
+pub fn deserialize_protobuf(layout: &Layout, serial: &[u8]) -> Result<Vec<u8>, Error> {
+    check_message(layout, &mut Vec::new())?;
+    let mut bytes = Vec::new();
+    read_message(layout, serial, &mut bytes)?;
+    Ok(bytes)
+}
+
+fn check_identifier(
+    name: &str,
+    path: &[PathSegment],
+    seen: &mut HashSet<String>,
+) -> Result<(), Error> {
+    let message = if !name.starts_with(|c: char| c.is_ascii_alphabetic())
+        || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    {
+        format!("`{}` is not a protobuf identifier", name)
+    } else if !seen.insert(name.to_string()) {
+        format!("duplicate name `{}`", name)
+    } else {
+        return Ok(());
+    };
+    Err(Error::Identifier {
+        path: format_path(path),
+        message,
+    })
+}
+
+// Checks that the layout is a struct whose field names and numbers, and
+// those of every struct within it, are valid.
+fn check_message(layout: &Layout, path: &mut Vec<PathSegment>) -> Result<(), Error> {
+    if layout.kind != LayoutKind::Struct {
+        return Err(Error::Protobuf(format!(
+            "`{}` is not a struct, so it cannot be a message",
+            layout.name
+        )));
+    }
+    let mut names = HashSet::new();
+    let mut numbers = HashSet::new();
+    let fields = layout.fields.as_ref().unwrap();
+    for (field, number) in fields.iter().zip(field_numbers(layout)) {
+        path.push(PathSegment::Field(field.name.clone()));
+        check_identifier(&field.name, path, &mut names)?;
+        let problem = if number == 0 || number > MAX_FIELD_NUMBER {
+            Some("is out of range")
+        } else if RESERVED_FIELD_NUMBERS.contains(&number) {
+            Some("is reserved by protobuf")
+        } else if !numbers.insert(number) {
+            Some("is used twice")
+        } else {
+            None
+        };
+        if let Some(problem) = problem {
+            return Err(Error::Protobuf(format!(
+                "field number {} of `{}` {}",
+                number,
+                format_path(path),
+                problem
+            )));
+        }
+        let mut element = field;
+        while element.kind == LayoutKind::Array {
+            element = element.fields.as_ref().unwrap().first().unwrap();
+        }
+        if element.kind == LayoutKind::Struct {
+            check_message(element, path)?;
+        }
+        path.pop();
+    }
+    Ok(())
+}
+
+fn field_numbers(layout: &Layout) -> Vec<u32> {
+    layout
+        .fields
+        .as_ref()
+        .unwrap()
+        .iter()
+        .enumerate()
+        .map(|(index, field)| field.field_number.unwrap_or(index as u32 + 1))
+        .collect()
+}
+
+// The message standing in for an array element that is itself an array.
+fn wrapper(array: &Layout) -> Layout {
+    Layout {
+        name: array.name.clone(),
+        kind: LayoutKind::Struct,
+        fields: Some(vec![Layout {
+            name: String::from("items"),
+            field_number: None,
+            ..array.clone()
+        }]),
+        default: None,
+        field_number: None,
+    }
+}
+
+fn scalar_type(kind: &LayoutKind) -> &'static str {
+    match kind {
+        LayoutKind::Array | LayoutKind::Struct => unreachable!(),
+        LayoutKind::String => "string",
+        LayoutKind::Bool => "bool",
+        LayoutKind::U8 | LayoutKind::U16 | LayoutKind::U32 => "uint32",
+        LayoutKind::U64 => "uint64",
+        LayoutKind::I8 | LayoutKind::I16 | LayoutKind::I32 => "sint32",
+        LayoutKind::I64 => "sint64",
+        LayoutKind::F32 => "float",
+        LayoutKind::F64 => "double",
+    }
+}
+
+fn message_of(layout: &Layout, name: &str, depth: usize) -> String {
+    let indent = "  ".repeat(depth);
+    let mut fields = String::new();
+    let mut nested = String::new();
+    let mut nested_names = HashSet::new();
+    let mut nested_message = |message: &Layout, field_name: &str| {
+        let base_name = type_name(field_name);
+        let mut message_name = base_name.clone();
+        let mut suffix = 2;
+        while !nested_names.insert(message_name.clone()) {
+            message_name = format!("{}{}", base_name, suffix);
+            suffix += 1;
+        }
+        nested.push_str(&message_of(message, &message_name, depth + 1));
+        message_name
+    };
+    for (field, number) in layout
+        .fields
+        .as_ref()
+        .unwrap()
+        .iter()
+        .zip(field_numbers(layout))
+    {
+        let (label, field_type) = match field.kind {
+            LayoutKind::Array => {
+                let element = field.fields.as_ref().unwrap().first().unwrap();
+                let element_type = match element.kind {
+                    LayoutKind::Array => nested_message(&wrapper(element), &field.name),
+                    LayoutKind::Struct => nested_message(element, &field.name),
+                    ref kind => scalar_type(kind).to_string(),
+                };
+                ("repeated ", element_type)
+            }
+            LayoutKind::Struct => ("", nested_message(field, &field.name)),
+            ref kind => ("", scalar_type(kind).to_string()),
+        };
+        fields.push_str(&format!(
+            "{}  {}{} {} = {};\n",
+            indent, label, field_type, field.name, number
+        ));
+    }
+    format!(
+        "{}message {} {{\n{}{}{}}}\n",
+        indent, name, fields, nested, indent
+    )
+}
+
+fn wire_type_of(kind: &LayoutKind) -> u64 {
+    match kind {
+        LayoutKind::Array | LayoutKind::Struct | LayoutKind::String => LENGTH_DELIMITED,
+        LayoutKind::F32 => FIXED32,
+        LayoutKind::F64 => FIXED64,
+        _ => VARINT,
+    }
+}
+
+fn write_varint(serial: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        serial.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    serial.push(value as u8);
+}
+
+fn write_key(serial: &mut Vec<u8>, number: u32, wire_type: u64) {
+    write_varint(serial, (number as u64) << 3 | wire_type);
+}
+
+fn write_length_delimited(serial: &mut Vec<u8>, number: u32, payload: &[u8]) {
+    write_key(serial, number, LENGTH_DELIMITED);
+    write_varint(serial, payload.len() as u64);
+    serial.extend_from_slice(payload);
+}
+
+// Writes the value of a primitive without its key.
+fn write_primitive(kind: &LayoutKind, bytes: &[u8], serial: &mut Vec<u8>) {
+    let mut widened = [0; 8];
+    widened[..bytes.len()].copy_from_slice(bytes);
+    let unsigned = u64::from_le_bytes(widened);
+    match kind {
+        LayoutKind::F32 | LayoutKind::F64 => serial.extend_from_slice(bytes),
+        LayoutKind::I8 | LayoutKind::I16 | LayoutKind::I32 | LayoutKind::I64 => {
+            // Sign-extend, then zigzag encode.
+            let shift = 64 - 8 * bytes.len() as u32;
+            let signed = ((unsigned << shift) as i64) >> shift;
+            write_varint(serial, ((signed << 1) ^ (signed >> 63)) as u64);
+        }
+        _ => write_varint(serial, unsigned),
+    }
+}
+
+fn string_length(bytes: &[u8]) -> usize {
+    usize::from_le_bytes(bytes[..8].try_into().unwrap())
+}
+
+// Writes the fields of a struct's bytes, which are already validated, in
+// field number order, and returns how many bytes they span.
+fn write_message(layout: &Layout, bytes: &[u8], serial: &mut Vec<u8>) -> usize {
+    let mut offset = 0;
+    let mut encoded_fields = Vec::new();
+    for (field, number) in layout
+        .fields
+        .as_ref()
+        .unwrap()
+        .iter()
+        .zip(field_numbers(layout))
+    {
+        let field_bytes = &bytes[offset..];
+        let mut encoded = Vec::new();
+        offset += match field.kind {
+            LayoutKind::Array => write_repeated(field, number, field_bytes, &mut encoded),
+            LayoutKind::Struct => {
+                let mut payload = Vec::new();
+                let size = write_message(field, field_bytes, &mut payload);
+                write_length_delimited(&mut encoded, number, &payload);
+                size
+            }
+            LayoutKind::String => {
+                let length = string_length(field_bytes);
+                if length > 0 {
+                    write_length_delimited(&mut encoded, number, &field_bytes[8..(8 + length)]);
+                }
+                8 + length
+            }
+            ref kind => {
+                let size = field.primitive_size().unwrap();
+                if field_bytes[..size].iter().any(|byte| *byte != 0) {
+                    write_key(&mut encoded, number, wire_type_of(kind));
+                    write_primitive(kind, &field_bytes[..size], &mut encoded);
+                }
+                size
+            }
+        };
+        encoded_fields.push((number, encoded));
+    }
+    encoded_fields.sort_by_key(|(number, _)| *number);
+    for (_, encoded) in encoded_fields {
+        serial.extend_from_slice(&encoded);
+    }
+    offset
+}
+
+fn write_repeated(layout: &Layout, number: u32, bytes: &[u8], serial: &mut Vec<u8>) -> usize {
+    let element_count = string_length(bytes);
+    let element = layout.fields.as_ref().unwrap().first().unwrap();
+    let mut offset = 8;
+    if let Some(size) = element.primitive_size() {
+        let mut payload = Vec::new();
+        for _ in 0..element_count {
+            write_primitive(&element.kind, &bytes[offset..(offset + size)], &mut payload);
+            offset += size;
+        }
+        if element_count > 0 {
+            write_length_delimited(serial, number, &payload);
+        }
+        return offset;
+    }
+    let element_wrapper = wrapper(element);
+    for _ in 0..element_count {
+        let element_bytes = &bytes[offset..];
+        let mut payload = Vec::new();
+        offset += match element.kind {
+            LayoutKind::Array => write_message(&element_wrapper, element_bytes, &mut payload),
+            LayoutKind::Struct => write_message(element, element_bytes, &mut payload),
+            _ => {
+                let length = string_length(element_bytes);
+                payload.extend_from_slice(&element_bytes[8..(8 + length)]);
+                8 + length
+            }
+        };
+        write_length_delimited(serial, number, &payload);
+    }
+    offset
+}
+
+struct Reader<'a> {
+    serial: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, count: usize) -> Result<&'a [u8], Error> {
+        if count > self.serial.len() - self.offset {
+            return Err(Error::Protobuf(format!(
+                "{} bytes needed at byte {} of a {}-byte message",
+                count,
+                self.offset,
+                self.serial.len()
+            )));
+        }
+        let taken = &self.serial[self.offset..(self.offset + count)];
+        self.offset += count;
+        Ok(taken)
+    }
+
+    fn varint(&mut self) -> Result<u64, Error> {
+        let mut value = 0;
+        for shift in (0..64).step_by(7) {
+            let byte = self.take(1)?[0];
+            if shift == 63 && byte > 1 {
+                break;
+            }
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(Error::Protobuf(format!(
+            "varint overflows 64 bits at byte {}",
+            self.offset - 1
+        )))
+    }
+
+    fn length_delimited(&mut self) -> Result<&'a [u8], Error> {
+        let length = self.varint()?;
+        self.take(usize::try_from(length).unwrap_or(usize::MAX))
+    }
+
+    fn skip(&mut self, wire_type: u64) -> Result<(), Error> {
+        match wire_type {
+            VARINT => {
+                self.varint()?;
+            }
+            FIXED64 => {
+                self.take(8)?;
+            }
+            LENGTH_DELIMITED => {
+                self.length_delimited()?;
+            }
+            FIXED32 => {
+                self.take(4)?;
+            }
+            _ => {
+                return Err(Error::Protobuf(format!(
+                    "unsupported wire type {} at byte {}",
+                    wire_type, self.offset
+                )))
+            }
+        }
+        Ok(())
+    }
+}
+
+// A struct field's bincode bytes while its message is read: message fields
+// collect their payloads, which merge when concatenated.
+enum Slot {
+    Value(Vec<u8>),
+    Message(Vec<u8>),
+    Elements(usize, Vec<u8>),
+}
+
+fn expected_wire_type(layout: &Layout, expected: u64, found: u64) -> Error {
+    Error::Protobuf(format!(
+        "expected wire type {} for `{}`, found {}",
+        expected, layout.name, found
+    ))
+}
+
+// Reads one primitive value and appends its bincode bytes.
+fn read_primitive(layout: &Layout, reader: &mut Reader, bytes: &mut Vec<u8>) -> Result<(), Error> {
+    let size = layout.primitive_size().unwrap();
+    let value = match layout.kind {
+        LayoutKind::F32 | LayoutKind::F64 => {
+            bytes.extend_from_slice(reader.take(size)?);
+            return Ok(());
+        }
+        LayoutKind::Bool => {
+            bytes.push((reader.varint()? != 0) as u8);
+            return Ok(());
+        }
+        LayoutKind::I8 | LayoutKind::I16 | LayoutKind::I32 | LayoutKind::I64 => {
+            let zigzag = reader.varint()?;
+            ((zigzag >> 1) as i64 ^ -((zigzag & 1) as i64)) as i128
+        }
+        _ => reader.varint()? as i128,
+    };
+    let (min, max) = integer_range(&layout.kind);
+    if value < min || value > max {
+        return Err(Error::Protobuf(format!(
+            "{} is out of range for `{}` ({:?})",
+            value, layout.name, layout.kind
+        )));
+    }
+    bytes.extend_from_slice(&integer_bytes(&layout.kind, value));
+    Ok(())
+}
+
+fn read_string(layout: &Layout, payload: &[u8], bytes: &mut Vec<u8>) -> Result<(), Error> {
+    if std::str::from_utf8(payload).is_err() {
+        return Err(Error::Protobuf(format!(
+            "`{}` is not valid UTF-8",
+            layout.name
+        )));
+    }
+    bytes.extend_from_slice(&payload.len().to_le_bytes());
+    bytes.extend_from_slice(payload);
+    Ok(())
+}
+
+fn read_message(layout: &Layout, serial: &[u8], bytes: &mut Vec<u8>) -> Result<(), Error> {
+    let fields = layout.fields.as_ref().unwrap();
+    let numbers = field_numbers(layout);
+    let mut slots = fields
+        .iter()
+        .map(|field| match field.kind {
+            LayoutKind::Array => Slot::Elements(0, Vec::new()),
+            LayoutKind::Struct => Slot::Message(Vec::new()),
+            LayoutKind::String => Slot::Value(0usize.to_le_bytes().to_vec()),
+            _ => Slot::Value(vec![0; field.primitive_size().unwrap()]),
+        })
+        .collect::<Vec<_>>();
+
+    let mut reader = Reader { serial, offset: 0 };
+    while reader.offset < serial.len() {
+        let key = reader.varint()?;
+        let wire_type = key & 7;
+        let index = match numbers.iter().position(|number| *number as u64 == key >> 3) {
+            Some(index) => index,
+            None => {
+                reader.skip(wire_type)?;
+                continue;
+            }
+        };
+        let field = &fields[index];
+        match (&mut slots[index], &field.kind) {
+            (Slot::Elements(count, element_bytes), LayoutKind::Array) => {
+                let element = field.fields.as_ref().unwrap().first().unwrap();
+                if element.primitive_size().is_some() && wire_type == wire_type_of(&element.kind) {
+                    read_primitive(element, &mut reader, element_bytes)?;
+                    *count += 1;
+                    continue;
+                }
+                if wire_type != LENGTH_DELIMITED {
+                    return Err(expected_wire_type(field, LENGTH_DELIMITED, wire_type));
+                }
+                let payload = reader.length_delimited()?;
+                match element.kind {
+                    LayoutKind::Array => {
+                        read_message(&wrapper(element), payload, element_bytes)?;
+                        *count += 1;
+                    }
+                    LayoutKind::Struct => {
+                        read_message(element, payload, element_bytes)?;
+                        *count += 1;
+                    }
+                    LayoutKind::String => {
+                        read_string(element, payload, element_bytes)?;
+                        *count += 1;
+                    }
+                    _ => {
+                        let mut packed = Reader {
+                            serial: payload,
+                            offset: 0,
+                        };
+                        while packed.offset < payload.len() {
+                            read_primitive(element, &mut packed, element_bytes)?;
+                            *count += 1;
+                        }
+                    }
+                }
+            }
+            (Slot::Message(payloads), _) => {
+                if wire_type != LENGTH_DELIMITED {
+                    return Err(expected_wire_type(field, LENGTH_DELIMITED, wire_type));
+                }
+                payloads.extend_from_slice(reader.length_delimited()?);
+            }
+            (Slot::Value(value), kind) => {
+                if wire_type != wire_type_of(&field.kind) {
+                    return Err(expected_wire_type(
+                        field,
+                        wire_type_of(&field.kind),
+                        wire_type,
+                    ));
+                }
+                value.clear();
+                if *kind == LayoutKind::String {
+                    read_string(field, reader.length_delimited()?, value)?;
+                } else {
+                    read_primitive(field, &mut reader, value)?;
+                }
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    for (field, slot) in fields.iter().zip(slots) {
+        match slot {
+            Slot::Value(value) => bytes.extend_from_slice(&value),
+            Slot::Message(payloads) => read_message(field, &payloads, bytes)?,
+            Slot::Elements(count, element_bytes) => {
+                bytes.extend_from_slice(&count.to_le_bytes());
+                bytes.extend_from_slice(&element_bytes);
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,77 @@
+use serde_json::Value;
+
+use crate::{DecodeLimits, Decoder, Error, Layout};
+
+// One blob out of a buffer of concatenated blobs of the same layout.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Record<'a> {
+    pub offset: usize,
+    pub bytes: &'a [u8],
+    pub value: Value,
+}
+
+// Yields the records of a buffer in order. Each record is decoded with its own
+// limits. After an error nothing more is yielded, since the start of the next
+// record cannot be known.
+pub struct RecordIter<'a> {
+    layout: &'a Layout,
+    bytes: &'a [u8],
+    limits: DecodeLimits,
+    offset: usize,
+    failed: bool,
+}
+
+impl<'a> RecordIter<'a> {
+    pub fn new(layout: &'a Layout, bytes: &'a [u8]) -> Self {
+        RecordIter {
+            layout,
+            bytes,
+            limits: DecodeLimits::default(),
+            offset: 0,
+            failed: false,
+        }
+    }
+
+    pub fn with_limits(mut self, limits: DecodeLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    // Where the next record starts.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
+impl<'a> Iterator for RecordIter<'a> {
+    type Item = Result<Record<'a>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed || self.offset == self.bytes.len() {
+            return None;
+        }
+        let start = self.offset;
+        let decoded = Decoder::new(self.bytes, &self.limits, true).decode(self.layout, start);
+        let (value, end) = match decoded {
+            Ok((_, end)) if end == start => {
+                self.failed = true;
+                return Some(Err(Error::Decode {
+                    offset: start,
+                    path: String::new(),
+                    message: String::from("records of this layout take no bytes"),
+                }));
+            }
+            Ok(decoded) => decoded,
+            Err(error) => {
+                self.failed = true;
+                return Some(Err(error));
+            }
+        };
+        self.offset = end;
+        Some(Ok(Record {
+            offset: start,
+            bytes: &self.bytes[start..end],
+            value,
+        }))
+    }
+}
@@ -0,0 +1,105 @@
+use std::collections::HashMap;
+
+use crate::{Error, HasLayout, Layout};
+
+// Component layouts by name and fingerprint. A name may have several
+// versions with different fingerprints; the latest registered one is the
+// name's current layout. Fingerprints only cover shape, so components of the
+// same shape (`Position` and `Velocity`, say) share one.
+#[derive(Clone, Debug, Default)]
+pub struct Registry {
+    layouts: Vec<Layout>,
+    by_name: HashMap<String, Vec<usize>>,
+    by_fingerprint: HashMap<u64, Vec<usize>>,
+    by_key: HashMap<(String, u64), usize>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Registry::default()
+    }
+
+    pub fn register<T: HasLayout>(&mut self) -> Result<u64, Error> {
+        self.register_layout(T::get_layout())
+    }
+
+    // Returns the layout's fingerprint. Registering an identical layout again
+    // does nothing; a different layout with the same name and fingerprint
+    // (other defaults or field numbers, say) is a conflict.
+    pub fn register_layout(&mut self, layout: Layout) -> Result<u64, Error> {
+        let fingerprint = layout.fingerprint();
+        let key = (layout.name.clone(), fingerprint);
+        if let Some(index) = self.by_key.get(&key) {
+            if self.layouts[*index] == layout {
+                return Ok(fingerprint);
+            }
+            return Err(Error::Conflict {
+                name: layout.name,
+                fingerprint,
+            });
+        }
+        let index = self.layouts.len();
+        self.by_name
+            .entry(layout.name.clone())
+            .or_default()
+            .push(index);
+        self.by_fingerprint
+            .entry(fingerprint)
+            .or_default()
+            .push(index);
+        self.by_key.insert(key, index);
+        self.layouts.push(layout);
+        Ok(fingerprint)
+    }
+
+    // The latest version registered under `name`.
+    pub fn layout(&self, name: &str) -> Option<&Layout> {
+        let index = self.by_name.get(name)?.last()?;
+        Some(&self.layouts[*index])
+    }
+
+    pub fn get(&self, name: &str, fingerprint: u64) -> Option<&Layout> {
+        let index = self.by_key.get(&(name.to_string(), fingerprint))?;
+        Some(&self.layouts[*index])
+    }
+
+    // Every version of `name`, oldest first.
+    pub fn versions<'a>(&'a self, name: &str) -> impl Iterator<Item = &'a Layout> + 'a {
+        self.by_name
+            .get(name)
+            .into_iter()
+            .flatten()
+            .map(move |index| &self.layouts[*index])
+    }
+
+    // Every layout with the fingerprint, in registration order.
+    pub fn by_fingerprint(&self, fingerprint: u64) -> impl Iterator<Item = &Layout> + '_ {
+        self.by_fingerprint
+            .get(&fingerprint)
+            .into_iter()
+            .flatten()
+            .map(move |index| &self.layouts[*index])
+    }
+
+    // Every layout, in registration order.
+    pub fn iter(&self) -> std::slice::Iter<'_, Layout> {
+        self.layouts.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.layouts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.layouts.is_empty()
+    }
+}
+
+impl<'a> IntoIterator for &'a Registry {
+    type Item = &'a Layout;
+    type IntoIter = std::slice::Iter<'a, Layout>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
@@ -0,0 +1,191 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::path::format_path;
+use crate::{Error, Layout, LayoutKind, PathSegment};
+
+const KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate",
+    "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl",
+    "in", "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+    "return", "self", "Self", "static", "struct", "super", "trait", "true", "try", "type",
+    "typeof", "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+// Names the generated module or the `#[component]` expansion already uses.
+const RESERVED_TYPE_NAMES: &[&str] = &[
+    "Box",
+    "Clone",
+    "Debug",
+    "Default",
+    "Err",
+    "Layout",
+    "LayoutKind",
+    "None",
+    "Ok",
+    "Option",
+    "PartialEq",
+    "Result",
+    "Self",
+    "Some",
+    "String",
+    "Vec",
+];
+
+impl Layout {
+    // Rust source with a `#[component]` struct for this layout and every
+    // struct nested in it, the reverse of what the macro derives. A nested
+    // struct is named after its field, since a field's layout carries the
+    // field's name rather than its type's; structs with the same fingerprint
+    // and field numbers are emitted once, and clashing names get a numeric
+    // suffix. Defaults are not carried over. Fails on field names
+    // `#[component]` cannot express.
+    pub fn to_rust_source(&self) -> Result<String, Error> {
+        let mut generator = Generator {
+            structs: Vec::new(),
+            by_fingerprint: HashMap::new(),
+            names: RESERVED_TYPE_NAMES
+                .iter()
+                .map(|name| name.to_string())
+                .collect(),
+            path: Vec::new(),
+        };
+        generator.rust_type(self, &self.name)?;
+        let mut source = String::from("// Generated from kind layouts. Do not edit.\n");
+        if !generator.structs.is_empty() {
+            source.push_str("\nuse project_kind::{Layout, LayoutKind};\n");
+            source.push_str("use project_kind_macros::component;\n");
+        }
+        for definition in generator.structs.iter() {
+            source.push('\n');
+            source.push_str(definition);
+        }
+        Ok(source)
+    }
+}
+
+struct Generator {
+    structs: Vec<String>,
+    by_fingerprint: HashMap<(u64, Vec<Option<u32>>), String>,
+    names: HashSet<String>,
+    path: Vec<PathSegment>,
+}
+
+impl Generator {
+    // `name` is what a struct found here is called: the field's name, which
+    // array elements inherit from their array.
+    fn rust_type(&mut self, layout: &Layout, name: &str) -> Result<String, Error> {
+        Ok(match layout.kind {
+            LayoutKind::Array => format!(
+                "Vec<{}>",
+                self.rust_type(layout.fields.as_ref().unwrap().first().unwrap(), name)?
+            ),
+            LayoutKind::Struct => self.struct_type(layout, name)?,
+            LayoutKind::String => String::from("String"),
+            LayoutKind::Bool => String::from("bool"),
+            LayoutKind::U8 => String::from("u8"),
+            LayoutKind::U16 => String::from("u16"),
+            LayoutKind::U32 => String::from("u32"),
+            LayoutKind::U64 => String::from("u64"),
+            LayoutKind::I8 => String::from("i8"),
+            LayoutKind::I16 => String::from("i16"),
+            LayoutKind::I32 => String::from("i32"),
+            LayoutKind::I64 => String::from("i64"),
+            LayoutKind::F32 => String::from("f32"),
+            LayoutKind::F64 => String::from("f64"),
+        })
+    }
+
+    fn struct_type(&mut self, layout: &Layout, name: &str) -> Result<String, Error> {
+        // Field numbers are not part of the fingerprint, but they are part of
+        // the generated struct.
+        let mut field_numbers = Vec::new();
+        collect_field_numbers(layout, &mut field_numbers);
+        let fingerprint = (layout.fingerprint(), field_numbers);
+        if let Some(type_name) = self.by_fingerprint.get(&fingerprint) {
+            return Ok(type_name.clone());
+        }
+        let base_name = type_name(name);
+        let mut type_name = base_name.clone();
+        let mut suffix = 2;
+        while self.names.contains(&type_name) {
+            type_name = format!("{}{}", base_name, suffix);
+            suffix += 1;
+        }
+        self.names.insert(type_name.clone());
+        self.by_fingerprint.insert(fingerprint, type_name.clone());
+
+        // Reserve the slot first so that a struct precedes the ones it uses.
+        let index = self.structs.len();
+        self.structs.push(String::new());
+        let mut definition = format!(
+            "#[component]\n#[derive(Debug, Clone, PartialEq)]\npub struct {} {{\n",
+            type_name
+        );
+        let mut field_names = HashSet::new();
+        for field in layout.fields.as_ref().unwrap().iter() {
+            self.path.push(PathSegment::Field(field.name.clone()));
+            self.check_field_name(&field.name, &mut field_names)?;
+            let field_type = self.rust_type(field, &field.name)?;
+            if let Some(number) = field.field_number {
+                definition.push_str(&format!("    #[proto(number = {})]\n", number));
+            }
+            definition.push_str(&format!("    pub {}: {},\n", field.name, field_type));
+            self.path.pop();
+        }
+        definition.push_str("}\n");
+        self.structs[index] = definition;
+        Ok(type_name)
+    }
+
+    // `#[component]` names each field's layout after the field's identifier,
+    // so raw identifiers and serde renames cannot stand in for other names.
+    fn check_field_name(&self, name: &str, seen: &mut HashSet<String>) -> Result<(), Error> {
+        let message = if !is_identifier(name) {
+            format!("`{}` is not a Rust identifier", name)
+        } else if KEYWORDS.contains(&name) {
+            format!("`{}` is a Rust keyword", name)
+        } else if !seen.insert(name.to_string()) {
+            format!("duplicate field `{}`", name)
+        } else {
+            return Ok(());
+        };
+        Err(Error::Identifier {
+            path: format_path(&self.path),
+            message,
+        })
+    }
+}
+
+fn collect_field_numbers(layout: &Layout, field_numbers: &mut Vec<Option<u32>>) {
+    for field in layout.fields.iter().flatten() {
+        if layout.kind == LayoutKind::Struct {
+            field_numbers.push(field.field_number);
+        }
+        collect_field_numbers(field, field_numbers);
+    }
+}
+
+fn is_identifier(name: &str) -> bool {
+    name != "_"
+        && name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+// Upper camel case, splitting on anything but ASCII letters and digits.
+pub(crate) fn type_name(name: &str) -> String {
+    let mut type_name = String::new();
+    for word in name.split(|c: char| !c.is_ascii_alphanumeric()) {
+        let mut chars = word.chars();
+        if let Some(first) = chars.next() {
+            type_name.push(first.to_ascii_uppercase());
+            type_name.extend(chars);
+        }
+    }
+    if type_name.is_empty() {
+        String::from("Unnamed")
+    } else if type_name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("Struct{}", type_name)
+    } else {
+        type_name
+    }
+}
@@ -0,0 +1,215 @@
+use crate::{get_in_slice, DecodeLimits, Error, Layout, LayoutKind};
+
+struct Column<'a> {
+    name: String,
+    layout: &'a Layout,
+}
+
+fn layout_error(message: String) -> Error {
+    Error::Csv {
+        message,
+        row: None,
+        column: None,
+    }
+}
+
+// Nested structs flatten into dotted column names, e.g. `pos.x`.
+fn collect_columns<'a>(
+    layout: &'a Layout,
+    prefix: &str,
+    columns: &mut Vec<Column<'a>>,
+) -> Result<(), Error> {
+    for field in layout.fields.as_ref().unwrap().iter() {
+        let name = if prefix.is_empty() {
+            field.name.clone()
+        } else {
+            format!("{}.{}", prefix, field.name)
+        };
+        match field.kind {
+            LayoutKind::Array => {
+                return Err(layout_error(format!(
+                    "field `{}` is an array and cannot be flattened into csv columns",
+                    name
+                )))
+            }
+            LayoutKind::Struct => collect_columns(field, &name, columns)?,
+            _ => columns.push(Column {
+                name,
+                layout: field,
+            }),
+        }
+    }
+    Ok(())
+}
+
+fn element_columns(layout: &Layout) -> Result<Vec<Column<'_>>, Error> {
+    if layout.kind != LayoutKind::Array {
+        return Err(layout_error(format!(
+            "layout `{}` is not an array",
+            layout.name
+        )));
+    }
+    let element_layout = layout.fields.as_ref().unwrap().first().unwrap();
+    if element_layout.kind != LayoutKind::Struct {
+        return Err(layout_error(format!(
+            "elements of `{}` are not structs",
+            layout.name
+        )));
+    }
+    let mut columns = Vec::new();
+    collect_columns(element_layout, "", &mut columns)?;
+    Ok(columns)
+}
+
+fn format_cell(layout: &Layout, bytes: &[u8]) -> String {
+    match layout.kind {
+        LayoutKind::String => {
+            let length_bytes: [u8; 8] = bytes[..8].try_into().unwrap();
+            let length = usize::from_le_bytes(length_bytes);
+            String::from_utf8(bytes[8..(8 + length)].to_vec()).unwrap()
+        }
+        LayoutKind::Bool => get_in_slice::<bool>(bytes, 0).unwrap().to_string(),
+        LayoutKind::U8 => get_in_slice::<u8>(bytes, 0).unwrap().to_string(),
+        LayoutKind::U16 => get_in_slice::<u16>(bytes, 0).unwrap().to_string(),
+        LayoutKind::U32 => get_in_slice::<u32>(bytes, 0).unwrap().to_string(),
+        LayoutKind::U64 => get_in_slice::<u64>(bytes, 0).unwrap().to_string(),
+        LayoutKind::I8 => get_in_slice::<i8>(bytes, 0).unwrap().to_string(),
+        LayoutKind::I16 => get_in_slice::<i16>(bytes, 0).unwrap().to_string(),
+        LayoutKind::I32 => get_in_slice::<i32>(bytes, 0).unwrap().to_string(),
+        LayoutKind::I64 => get_in_slice::<i64>(bytes, 0).unwrap().to_string(),
+        LayoutKind::F32 => get_in_slice::<f32>(bytes, 0).unwrap().to_string(),
+        LayoutKind::F64 => get_in_slice::<f64>(bytes, 0).unwrap().to_string(),
+        LayoutKind::Array | LayoutKind::Struct => unreachable!(),
+    }
+}
+
+fn parse_cell(layout: &Layout, cell: &str) -> Option<Vec<u8>> {
+    let bytes = match layout.kind {
+        LayoutKind::String => {
+            let mut bytes = cell.len().to_le_bytes().to_vec();
+            bytes.extend_from_slice(cell.as_bytes());
+            bytes
+        }
+        LayoutKind::Bool => vec![cell.parse::<bool>().ok()? as u8],
+        LayoutKind::U8 => cell.parse::<u8>().ok()?.to_le_bytes().to_vec(),
+        LayoutKind::U16 => cell.parse::<u16>().ok()?.to_le_bytes().to_vec(),
+        LayoutKind::U32 => cell.parse::<u32>().ok()?.to_le_bytes().to_vec(),
+        LayoutKind::U64 => cell.parse::<u64>().ok()?.to_le_bytes().to_vec(),
+        LayoutKind::I8 => cell.parse::<i8>().ok()?.to_le_bytes().to_vec(),
+        LayoutKind::I16 => cell.parse::<i16>().ok()?.to_le_bytes().to_vec(),
+        LayoutKind::I32 => cell.parse::<i32>().ok()?.to_le_bytes().to_vec(),
+        LayoutKind::I64 => cell.parse::<i64>().ok()?.to_le_bytes().to_vec(),
+        LayoutKind::F32 => cell.parse::<f32>().ok()?.to_le_bytes().to_vec(),
+        LayoutKind::F64 => cell.parse::<f64>().ok()?.to_le_bytes().to_vec(),
+        LayoutKind::Array | LayoutKind::Struct => unreachable!(),
+    };
+    Some(bytes)
+}
+
+fn csv_error(error: ::csv::Error) -> Error {
+    let row = error.position().map(|position| position.line() as usize);
+    Error::Csv {
+        message: error.to_string(),
+        row,
+        column: None,
+    }
+}
+
+pub fn to_csv(layout: &Layout, bytes: &[u8]) -> Result<String, Error> {
+    to_csv_with_limits(layout, bytes, &DecodeLimits::unlimited())
+}
+
+pub fn to_csv_with_limits(
+    layout: &Layout,
+    bytes: &[u8],
+    limits: &DecodeLimits,
+) -> Result<String, Error> {
+    let columns = element_columns(layout)?;
+    layout.check_with_limits(bytes, limits)?;
+    let mut writer = ::csv::Writer::from_writer(Vec::new());
+    writer
+        .write_record(columns.iter().map(|column| column.name.as_str()))
+        .unwrap();
+    let element_count_bytes: [u8; 8] = bytes[..8].try_into().unwrap();
+    let element_count = usize::from_le_bytes(element_count_bytes);
+    let mut offset = 8;
+    for _ in 0..element_count {
+        let mut record = Vec::new();
+        for column in columns.iter() {
+            record.push(format_cell(column.layout, &bytes[offset..]));
+            offset += column.layout.size_in_bytes(&bytes[offset..]).unwrap();
+        }
+        writer.write_record(&record).unwrap();
+    }
+    let serial = writer.into_inner().unwrap();
+    Ok(String::from_utf8(serial).unwrap())
+}
+
+pub fn from_csv(layout: &Layout, serial: &str) -> Result<Vec<u8>, Error> {
+    let columns = element_columns(layout)?;
+    let mut reader = ::csv::ReaderBuilder::new()
+        .has_headers(false)
+        .from_reader(serial.as_bytes());
+    let mut records = reader.records();
+    let header = match records.next() {
+        Some(header) => header.map_err(csv_error)?,
+        None => {
+            return Err(Error::Csv {
+                message: String::from("missing header row"),
+                row: Some(1),
+                column: None,
+            })
+        }
+    };
+    // Map each layout column to its position in the file so column order is free.
+    let mut positions = Vec::new();
+    for column in columns.iter() {
+        match header.iter().position(|name| name == column.name) {
+            Some(position) => positions.push(position),
+            None => {
+                return Err(Error::Csv {
+                    message: format!("missing column `{}`", column.name),
+                    row: Some(1),
+                    column: None,
+                })
+            }
+        }
+    }
+    for (position, name) in header.iter().enumerate() {
+        if !columns.iter().any(|column| column.name == name) {
+            return Err(Error::Csv {
+                message: format!("unknown column `{}`", name),
+                row: Some(1),
+                column: Some(position + 1),
+            });
+        }
+    }
+    let mut bytes = vec![0u8; 8];
+    let mut element_count: usize = 0;
+    for (index, record) in records.enumerate() {
+        let record = record.map_err(csv_error)?;
+        let row = match record.position() {
+            Some(position) => position.line() as usize,
+            None => index + 2,
+        };
+        for (column, position) in columns.iter().zip(positions.iter()) {
+            let cell = record.get(*position).ok_or_else(|| Error::Csv {
+                message: format!("missing value for `{}`", column.name),
+                row: Some(row),
+                column: Some(position + 1),
+            })?;
+            let cell_bytes = parse_cell(column.layout, cell).ok_or_else(|| Error::Csv {
+                message: format!(
+                    "invalid {:?} value `{}` for `{}`",
+                    column.layout.kind, cell, column.name
+                ),
+                row: Some(row),
+                column: Some(position + 1),
+            })?;
+            bytes.extend_from_slice(&cell_bytes);
+        }
+        element_count += 1;
+    }
+    bytes[..8].copy_from_slice(&element_count.to_le_bytes());
+    Ok(bytes)
+}
@@ -0,0 +1,449 @@
+use std::collections::HashSet;
+
+use crate::{Layout, LayoutKind};
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TypeScriptOptions {
+    // Types u64 and i64 as `bigint | number` rather than `number`, which
+    // cannot hold every 64-bit value; decoders then return `bigint`s.
+    pub bigint: bool,
+    // Adds `decodeX`/`encodeX` functions for the bincode bytes of each layout.
+    pub codec: bool,
+}
+
+// Generates a TypeScript module with an `interface` (or a `type` alias for
+// layouts that are not structs) per layout. Nested structs are typed inline,
+// since a field's layout is named after the field rather than its type.
+// Layouts with clashing names, such as two versions of one component, get a
+// numeric suffix: `Position`, then `Position2`.
+pub fn to_typescript(layouts: &[Layout], options: &TypeScriptOptions) -> String {
+    let names = unique_type_names(layouts);
+    let mut source = String::from("// Generated from kind layouts. Do not edit.\n");
+    for (layout, name) in layouts.iter().zip(names.iter()) {
+        source.push('\n');
+        if layout.kind == LayoutKind::Struct {
+            source.push_str(&format!(
+                "export interface {} {}\n",
+                name,
+                type_of(layout, options, 0)
+            ));
+        } else {
+            source.push_str(&format!(
+                "export type {} = {};\n",
+                name,
+                type_of(layout, options, 0)
+            ));
+        }
+    }
+    if options.codec {
+        source.push('\n');
+        source.push_str(RUNTIME);
+        for (layout, name) in layouts.iter().zip(names.iter()) {
+            source.push('\n');
+            source.push_str(&codec_of(layout, name, options));
+        }
+    }
+    source
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) if first.is_ascii_alphabetic() || first == '_' || first == '$' => {
+            chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+        }
+        _ => false,
+    }
+}
+
+fn type_name(name: &str) -> String {
+    let name = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect::<String>();
+    if name.starts_with(|c: char| c.is_ascii_digit()) || name.is_empty() {
+        format!("_{}", name)
+    } else {
+        name
+    }
+}
+
+// The runtime's classes are taken from the start.
+fn unique_type_names(layouts: &[Layout]) -> Vec<String> {
+    let mut taken = ["KindReader", "KindWriter"]
+        .iter()
+        .map(|name| name.to_string())
+        .collect::<HashSet<_>>();
+    layouts
+        .iter()
+        .map(|layout| {
+            let base_name = type_name(&layout.name);
+            let mut name = base_name.clone();
+            let mut suffix = 2;
+            while !taken.insert(name.clone()) {
+                name = format!("{}{}", base_name, suffix);
+                suffix += 1;
+            }
+            name
+        })
+        .collect()
+}
+
+fn property_key(name: &str) -> String {
+    if is_identifier(name) {
+        name.to_string()
+    } else {
+        format!("{:?}", name)
+    }
+}
+
+fn property_access(object: &str, name: &str) -> String {
+    if is_identifier(name) {
+        format!("{}.{}", object, name)
+    } else {
+        format!("{}[{:?}]", object, name)
+    }
+}
+
+fn type_of(layout: &Layout, options: &TypeScriptOptions, depth: usize) -> String {
+    match layout.kind {
+        LayoutKind::Array => {
+            let element_type = type_of(element_layout(layout), options, depth);
+            if element_type.contains(' ') && !element_type.starts_with('{') {
+                format!("({})[]", element_type)
+            } else {
+                format!("{}[]", element_type)
+            }
+        }
+        LayoutKind::Struct => {
+            let indent = "  ".repeat(depth + 1);
+            let mut body = String::from("{\n");
+            for field in layout.fields.as_ref().unwrap().iter() {
+                body.push_str(&format!(
+                    "{}{}: {};\n",
+                    indent,
+                    property_key(&field.name),
+                    type_of(field, options, depth + 1)
+                ));
+            }
+            body.push_str(&"  ".repeat(depth));
+            body.push('}');
+            body
+        }
+        LayoutKind::String => String::from("string"),
+        LayoutKind::Bool => String::from("boolean"),
+        LayoutKind::U64 | LayoutKind::I64 if options.bigint => String::from("bigint | number"),
+        _ => String::from("number"),
+    }
+}
+
+fn element_layout(layout: &Layout) -> &Layout {
+    layout.fields.as_ref().unwrap().first().unwrap()
+}
+
+fn reader_method(kind: &LayoutKind) -> &'static str {
+    match kind {
+        LayoutKind::Bool => "bool",
+        LayoutKind::U8 => "u8",
+        LayoutKind::U16 => "u16",
+        LayoutKind::U32 => "u32",
+        LayoutKind::U64 => "u64",
+        LayoutKind::I8 => "i8",
+        LayoutKind::I16 => "i16",
+        LayoutKind::I32 => "i32",
+        LayoutKind::I64 => "i64",
+        LayoutKind::F32 => "f32",
+        LayoutKind::F64 => "f64",
+        LayoutKind::String => "string",
+        LayoutKind::Array | LayoutKind::Struct => unreachable!(),
+    }
+}
+
+fn decode_expression(layout: &Layout, options: &TypeScriptOptions, depth: usize) -> String {
+    match layout.kind {
+        LayoutKind::Array => {
+            let element_layout = element_layout(layout);
+            let element = decode_expression(element_layout, options, depth);
+            if element_layout.kind == LayoutKind::Struct {
+                format!("r.array(() => ({}))", element)
+            } else {
+                format!("r.array(() => {})", element)
+            }
+        }
+        LayoutKind::Struct => {
+            let indent = "  ".repeat(depth + 1);
+            let mut expression = String::from("{\n");
+            for field in layout.fields.as_ref().unwrap().iter() {
+                expression.push_str(&format!(
+                    "{}{}: {},\n",
+                    indent,
+                    property_key(&field.name),
+                    decode_expression(field, options, depth + 1)
+                ));
+            }
+            expression.push_str(&"  ".repeat(depth));
+            expression.push('}');
+            expression
+        }
+        LayoutKind::U64 | LayoutKind::I64 if !options.bigint => {
+            format!("Number(r.{}())", reader_method(&layout.kind))
+        }
+        ref kind => format!("r.{}()", reader_method(kind)),
+    }
+}
+
+// Appends statements writing `value` to `w`; `depth` keeps the names of
+// nested array callback parameters distinct.
+fn encode_statements(layout: &Layout, value: &str, depth: usize, indent: usize, code: &mut String) {
+    let prefix = "  ".repeat(indent);
+    match layout.kind {
+        LayoutKind::Array => {
+            let element = format!("e{}", depth);
+            let element_layout = element_layout(layout);
+            if matches!(element_layout.kind, LayoutKind::Array | LayoutKind::Struct) {
+                code.push_str(&format!(
+                    "{}w.array({}, ({}) => {{\n",
+                    prefix, value, element
+                ));
+                encode_statements(element_layout, &element, depth + 1, indent + 1, code);
+                code.push_str(&format!("{}}});\n", prefix));
+            } else {
+                code.push_str(&format!(
+                    "{}w.array({}, ({}) => w.{}({}));\n",
+                    prefix,
+                    value,
+                    element,
+                    reader_method(&element_layout.kind),
+                    element
+                ));
+            }
+        }
+        LayoutKind::Struct => {
+            for field in layout.fields.as_ref().unwrap().iter() {
+                let field_value = property_access(value, &field.name);
+                encode_statements(field, &field_value, depth, indent, code);
+            }
+        }
+        ref kind => code.push_str(&format!(
+            "{}w.{}({});\n",
+            prefix,
+            reader_method(kind),
+            value
+        )),
+    }
+}
+
+fn codec_of(layout: &Layout, name: &str, options: &TypeScriptOptions) -> String {
+    let mut code = format!(
+        "export function decode{}(bytes: Uint8Array): {} {{\n  const r = new KindReader(bytes);\n  const value: {} = {};\n  r.end();\n  return value;\n}}\n\n",
+        name,
+        name,
+        name,
+        decode_expression(layout, options, 1)
+    );
+    code.push_str(&format!(
+        "export function encode{}(value: {}): Uint8Array {{\n  const w = new KindWriter();\n",
+        name, name
+    ));
+    encode_statements(layout, "value", 0, 1, &mut code);
+    code.push_str("  return w.finish();\n}\n");
+    code
+}
+
+// Readers and writers for the bincode encoding: little-endian integers and
+// floats, one byte bools and u64 length prefixes for strings and arrays.
+const RUNTIME: &str = r#"const utf8Decoder = new TextDecoder("utf-8", { fatal: true });
+const utf8Encoder = new TextEncoder();
+
+class KindReader {
+  private bytes: Uint8Array;
+  private view: DataView;
+  private offset = 0;
+
+  constructor(bytes: Uint8Array) {
+    this.bytes = bytes;
+    this.view = new DataView(bytes.buffer, bytes.byteOffset, bytes.byteLength);
+  }
+
+  private take(length: number): number {
+    if (length > this.bytes.length - this.offset) {
+      throw new RangeError(`truncated at byte ${this.offset}: needs ${length} bytes`);
+    }
+    const offset = this.offset;
+    this.offset += length;
+    return offset;
+  }
+
+  private length(): number {
+    const length = this.view.getBigUint64(this.take(8), true);
+    if (length > BigInt(this.bytes.length - this.offset)) {
+      throw new RangeError(`length ${length} at byte ${this.offset - 8} exceeds the input`);
+    }
+    return Number(length);
+  }
+
+  end(): void {
+    if (this.offset !== this.bytes.length) {
+      throw new RangeError(`${this.bytes.length - this.offset} trailing bytes`);
+    }
+  }
+
+  bool(): boolean {
+    const byte = this.view.getUint8(this.take(1));
+    if (byte > 1) {
+      throw new RangeError(`invalid bool byte ${byte} at byte ${this.offset - 1}`);
+    }
+    return byte === 1;
+  }
+
+  u8(): number { return this.view.getUint8(this.take(1)); }
+  u16(): number { return this.view.getUint16(this.take(2), true); }
+  u32(): number { return this.view.getUint32(this.take(4), true); }
+  u64(): bigint { return this.view.getBigUint64(this.take(8), true); }
+  i8(): number { return this.view.getInt8(this.take(1)); }
+  i16(): number { return this.view.getInt16(this.take(2), true); }
+  i32(): number { return this.view.getInt32(this.take(4), true); }
+  i64(): bigint { return this.view.getBigInt64(this.take(8), true); }
+  f32(): number { return this.view.getFloat32(this.take(4), true); }
+  f64(): number { return this.view.getFloat64(this.take(8), true); }
+
+  string(): string {
+    const length = this.length();
+    const offset = this.take(length);
+    return utf8Decoder.decode(this.bytes.subarray(offset, offset + length));
+  }
+
+  array<T>(element: () => T): T[] {
+    const length = this.length();
+    const values: T[] = [];
+    for (let index = 0; index < length; index++) {
+      values.push(element());
+    }
+    return values;
+  }
+}
+
+class KindWriter {
+  private bytes = new Uint8Array(64);
+  private view = new DataView(this.bytes.buffer);
+  private offset = 0;
+
+  // Returns where `length` more bytes go, growing the buffer first, so that
+  // `this.view` must only be read after calling it.
+  private reserve(length: number): number {
+    if (this.offset + length > this.bytes.length) {
+      const bytes = new Uint8Array(Math.max(this.bytes.length * 2, this.offset + length));
+      bytes.set(this.bytes);
+      this.bytes = bytes;
+      this.view = new DataView(bytes.buffer);
+    }
+    const offset = this.offset;
+    this.offset += length;
+    return offset;
+  }
+
+  private integer(value: number, min: number, max: number): number {
+    if (!Number.isInteger(value) || value < min || value > max) {
+      throw new RangeError(`${value} is out of range (${min}..=${max})`);
+    }
+    return value;
+  }
+
+  private bigInteger(value: bigint | number, min: bigint, max: bigint): bigint {
+    const integer = BigInt(value);
+    if (integer < min || integer > max) {
+      throw new RangeError(`${value} is out of range (${min}..=${max})`);
+    }
+    return integer;
+  }
+
+  private length(length: number): void {
+    const offset = this.reserve(8);
+    this.view.setBigUint64(offset, BigInt(length), true);
+  }
+
+  finish(): Uint8Array {
+    return this.bytes.slice(0, this.offset);
+  }
+
+  bool(value: boolean): void {
+    const checked = value ? 1 : 0;
+    const offset = this.reserve(1);
+    this.view.setUint8(offset, checked);
+  }
+
+  u8(value: number): void {
+    const checked = this.integer(value, 0, 0xff);
+    const offset = this.reserve(1);
+    this.view.setUint8(offset, checked);
+  }
+
+  u16(value: number): void {
+    const checked = this.integer(value, 0, 0xffff);
+    const offset = this.reserve(2);
+    this.view.setUint16(offset, checked, true);
+  }
+
+  u32(value: number): void {
+    const checked = this.integer(value, 0, 0xffffffff);
+    const offset = this.reserve(4);
+    this.view.setUint32(offset, checked, true);
+  }
+
+  u64(value: bigint | number): void {
+    const checked = this.bigInteger(value, 0n, (1n << 64n) - 1n);
+    const offset = this.reserve(8);
+    this.view.setBigUint64(offset, checked, true);
+  }
+
+  i8(value: number): void {
+    const checked = this.integer(value, -0x80, 0x7f);
+    const offset = this.reserve(1);
+    this.view.setInt8(offset, checked);
+  }
+
+  i16(value: number): void {
+    const checked = this.integer(value, -0x8000, 0x7fff);
+    const offset = this.reserve(2);
+    this.view.setInt16(offset, checked, true);
+  }
+
+  i32(value: number): void {
+    const checked = this.integer(value, -0x80000000, 0x7fffffff);
+    const offset = this.reserve(4);
+    this.view.setInt32(offset, checked, true);
+  }
+
+  i64(value: bigint | number): void {
+    const checked = this.bigInteger(value, -(1n << 63n), (1n << 63n) - 1n);
+    const offset = this.reserve(8);
+    this.view.setBigInt64(offset, checked, true);
+  }
+
+  f32(value: number): void {
+    const checked = value;
+    const offset = this.reserve(4);
+    this.view.setFloat32(offset, checked, true);
+  }
+
+  f64(value: number): void {
+    const checked = value;
+    const offset = this.reserve(8);
+    this.view.setFloat64(offset, checked, true);
+  }
+
+  string(value: string): void {
+    const encoded = utf8Encoder.encode(value);
+    this.length(encoded.length);
+    const offset = this.reserve(encoded.length);
+    this.bytes.set(encoded, offset);
+  }
+
+  array<T>(values: T[], element: (value: T) => void): void {
+    this.length(values.length);
+    for (const value of values) {
+      element(value);
+    }
+  }
+}
+"#;
@@ -0,0 +1,42 @@
+use crate::path::format_path;
+use crate::{Error, Layout, LayoutKind, PathSegment};
+
+impl Layout {
+    // Checks that an array has exactly one element layout, a struct has a
+    // list of fields and a primitive has none. Layouts from `#[component]`
+    // always pass; ones read from JSON should be checked before use, since
+    // decoding and encoding rely on this shape.
+    pub fn validate(&self) -> Result<(), Error> {
+        validate_layout(self, &mut Vec::new())
+    }
+}
+
+fn validate_layout(layout: &Layout, path: &mut Vec<PathSegment>) -> Result<(), Error> {
+    let message = match (&layout.kind, &layout.fields) {
+        (LayoutKind::Array, Some(fields)) if fields.len() == 1 => {
+            path.push(PathSegment::Index(0));
+            validate_layout(&fields[0], path)?;
+            path.pop();
+            return Ok(());
+        }
+        (LayoutKind::Array, Some(fields)) => {
+            format!("an array needs one element layout, found {}", fields.len())
+        }
+        (LayoutKind::Array, None) => String::from("an array needs one element layout"),
+        (LayoutKind::Struct, Some(fields)) => {
+            for field in fields.iter() {
+                path.push(PathSegment::Field(field.name.clone()));
+                validate_layout(field, path)?;
+                path.pop();
+            }
+            return Ok(());
+        }
+        (LayoutKind::Struct, None) => String::from("a struct needs a list of fields"),
+        (kind, Some(_)) => format!("{:?} cannot have fields", kind),
+        (_, None) => return Ok(()),
+    };
+    Err(Error::Layout {
+        path: format_path(path),
+        message,
+    })
+}
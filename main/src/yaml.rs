@@ -0,0 +1,39 @@
+use serde::de::DeserializeSeed;
+
+use crate::{DecodeLimits, Error, Layout, LayoutBytes, LayoutSeed};
+
+pub fn serialize_yaml(layout: &Layout, bytes: &[u8]) -> Result<String, Error> {
+    serialize_yaml_with_limits(layout, bytes, &DecodeLimits::unlimited())
+}
+
+pub fn serialize_yaml_with_limits(
+    layout: &Layout,
+    bytes: &[u8],
+    limits: &DecodeLimits,
+) -> Result<String, Error> {
+    layout.check_with_limits(bytes, limits)?;
+    Ok(serde_yaml::to_string(&LayoutBytes(layout, bytes)).unwrap())
+}
+
+pub fn deserialize_yaml(layout: &Layout, serial: &str) -> Result<Vec<u8>, Error> {
+    let deserializer = serde_yaml::Deserializer::from_str(serial);
+    LayoutSeed(layout)
+        .deserialize(deserializer)
+        .map_err(yaml_error)
+}
+
+fn yaml_error(error: serde_yaml::Error) -> Error {
+    let location = error.location();
+    let mut message = error.to_string();
+    if let Some(location) = &location {
+        let suffix = format!(" at line {} column {}", location.line(), location.column());
+        if let Some(stripped) = message.strip_suffix(&suffix) {
+            message = stripped.to_string();
+        }
+    }
+    Error::Yaml {
+        message,
+        line: location.as_ref().map(|location| location.line()),
+        column: location.as_ref().map(|location| location.column()),
+    }
+}
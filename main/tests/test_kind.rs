@@ -0,0 +1,101 @@
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Output, Stdio};
+
+const PERSON: &str = r#"{"name":"Person","kind":"Struct","fields":[
+    {"name":"age","kind":"U8"},
+    {"name":"name","kind":"String"}
+]}"#;
+
+const PERSON_BYTES: &[u8] = b"\x07\x02\x00\x00\x00\x00\x00\x00\x00Al";
+
+fn schema(file_name: &str, contents: &str) -> PathBuf {
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(file_name);
+    std::fs::write(&path, contents).unwrap();
+    path
+}
+
+// Runs `kind` with `stdin` as its input.
+fn kind(args: &[&str], schema: &PathBuf, stdin: &[u8]) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_kind"))
+        .args(args)
+        .arg("--schema")
+        .arg(schema)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    // `kind` may exit before reading its input, e.g. on a bad schema.
+    let _ = child.stdin.take().unwrap().write_all(stdin);
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn to_json_and_back() {
+    let person = schema("person.layout.json", PERSON);
+    let output = kind(&["to-json"], &person, PERSON_BYTES);
+    assert!(output.status.success());
+    assert_eq!(output.stdout, b"{\"age\":7,\"name\":\"Al\"}\n");
+
+    let output = kind(&["to-json", "--pretty"], &person, PERSON_BYTES);
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "{\n  \"age\": 7,\n  \"name\": \"Al\"\n}\n"
+    );
+
+    let output = kind(&["to-bin"], &person, br#"{"name":"Al","age":7}"#);
+    assert!(output.status.success());
+    assert_eq!(output.stdout, PERSON_BYTES);
+    let output = kind(&["to-bin"], &person, br#"{"name":"Al","age":700}"#);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn inspect_bytes() {
+    let person = schema("inspect.layout.json", PERSON);
+    let output = kind(&["inspect"], &person, PERSON_BYTES);
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "00000000  07                       .         age = 7
+00000001  02 00 00 00 00 00 00 00  ........  name (length) = 2
+00000009  41 6c                    Al        name = \"Al\"
+"
+    );
+}
+
+#[test]
+fn bad_schema_is_an_error() {
+    let cases = [
+        (
+            r#"{"name":"Bytes","kind":"Array","fields":[]}"#,
+            "invalid layout: an array needs one element layout, found 0",
+        ),
+        (
+            r#"{"name":"Person","kind":"Struct","fields":null}"#,
+            "invalid layout: a struct needs a list of fields",
+        ),
+        (
+            r#"{"name":"Person","kind":"Struct","fields":[{"name":"tags","kind":"Array"}]}"#,
+            "invalid layout at `tags`: an array needs one element layout",
+        ),
+        (
+            r#"{"name":"Age","kind":"U8","fields":[]}"#,
+            "invalid layout: U8 cannot have fields",
+        ),
+        (
+            r#"{"name":"Person","kind":"Tuple"}"#,
+            "unknown variant `Tuple`",
+        ),
+    ];
+    for (index, (contents, message)) in cases.iter().enumerate() {
+        let bad = schema(&format!("bad{}.layout.json", index), contents);
+        let output = kind(&["to-json"], &bad, PERSON_BYTES);
+        assert_eq!(output.status.code(), Some(1));
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert!(stderr.starts_with("kind: "), "{}", stderr);
+        assert!(stderr.contains(message), "{}", stderr);
+    }
+}
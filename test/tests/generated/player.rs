@@ -0,0 +1,43 @@
+// Generated from kind layouts. Do not edit.
+
+use project_kind::{Layout, LayoutKind};
+use project_kind_macros::component;
+
+#[component]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Player {
+    pub name: String,
+    pub position: Position,
+    pub home: Home,
+    pub work: Home,
+    pub inventory: Vec<Inventory>,
+    pub scores: Vec<Vec<u64>>,
+}
+
+#[component]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Position {
+    pub x: f32,
+    pub y: f32,
+}
+
+#[component]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Home {
+    pub street: String,
+    pub number: u16,
+}
+
+#[component]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Inventory {
+    pub id: u32,
+    pub position: Position2,
+}
+
+#[component]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Position2 {
+    pub x: i32,
+    pub y: i32,
+}
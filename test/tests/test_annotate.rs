@@ -0,0 +1,65 @@
+use project_kind::{HasLayout, Layout, LayoutKind};
+use project_kind_macros::component;
+
+#[component]
+#[derive(Debug, Clone, PartialEq)]
+struct Person {
+    name: String,
+    age: u8,
+    scores: Vec<u16>,
+}
+
+fn person_bytes() -> Vec<u8> {
+    let person_typed = Person {
+        name: String::from("Andy"),
+        age: 27,
+        scores: vec![7, 300],
+    };
+    bincode::serialize(&person_typed).unwrap()
+}
+
+#[test]
+fn annotate_valid_bytes() {
+    let person_layout = Person::get_layout();
+    let annotation = person_layout.annotate(&person_bytes());
+
+    assert!(annotation.divergence.is_none());
+    let annotation_expected = "\
+00000000  04 00 00 00 00 00 00 00  ........  name (length) = 4
+00000008  41 6e 64 79              Andy      name = \"Andy\"
+0000000c  1b                       .         age = 27
+0000000d  02 00 00 00 00 00 00 00  ........  scores (length) = 2
+00000015  07 00                    ..        scores[0] = 7
+00000017  2c 01                    ,.        scores[1] = 300
+";
+    assert_eq!(annotation.to_string(), annotation_expected);
+}
+
+#[test]
+fn annotate_truncated_bytes() {
+    let person_layout = Person::get_layout();
+    let mut person_bytes = person_bytes();
+    person_bytes.truncate(24);
+    let annotation = person_layout.annotate(&person_bytes);
+
+    let divergence = annotation.divergence.clone().unwrap();
+    assert_eq!(divergence.offset, 23);
+    assert_eq!(divergence.path, "scores[1]");
+    assert!(annotation
+        .to_string()
+        .ends_with("!! 00000017  scores[1]: truncated: needs 2 bytes, 1 remain\n00000017  2c                       ,         <unparsed>\n"));
+}
+
+#[test]
+fn annotate_trailing_bytes() {
+    let person_layout = Person::get_layout();
+    let mut person_bytes = person_bytes();
+    person_bytes.extend_from_slice(&[0xff, 0x41]);
+    let annotation = person_layout.annotate(&person_bytes);
+
+    let divergence = annotation.divergence.clone().unwrap();
+    assert_eq!(divergence.offset, 25);
+    assert!(annotation
+        .to_string()
+        .ends_with("!! 00000019  2 trailing bytes after end of layout\n00000019  ff 41                    .A        <unparsed>\n"));
+}
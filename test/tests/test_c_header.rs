@@ -0,0 +1,269 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+use project_kind::{to_c_header, Error, HasLayout, Layout, LayoutKind};
+use project_kind_macros::component;
+
+#[component]
+#[derive(Debug, Clone, PartialEq)]
+struct Position {
+    x: f32,
+    y: f32,
+}
+
+#[component]
+#[derive(Debug, Clone, PartialEq)]
+struct Particle {
+    id: u32,
+    alive: bool,
+    position: Position,
+    mass: f64,
+    charge: i8,
+    spin: i16,
+    tick: u64,
+}
+
+#[component]
+#[derive(Debug, Clone, PartialEq)]
+struct Item {
+    id: u16,
+    tags: Vec<String>,
+}
+
+#[component]
+#[derive(Debug, Clone, PartialEq)]
+struct Person {
+    name: String,
+    age: u8,
+    position: Position,
+    inventory: Vec<Item>,
+    grid: Vec<Vec<i64>>,
+    alive: bool,
+}
+
+// Compiles `program` against the generated header and runs it with `args`.
+fn run_c(name: &str, header: &str, program: &str, args: &[PathBuf]) -> String {
+    let directory = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name);
+    fs::create_dir_all(&directory).unwrap();
+    fs::write(directory.join("kind.h"), header).unwrap();
+    fs::write(directory.join("main.c"), program).unwrap();
+    let status = Command::new("cc")
+        .current_dir(&directory)
+        .args(["-std=c11", "-Wall", "-Wextra", "-Werror", "-pedantic"])
+        .args(["main.c", "-o", "main"])
+        .status()
+        .expect("Could not run the C compiler");
+    assert!(status.success());
+    let output = Command::new(directory.join("main"))
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    String::from_utf8(output.stdout).unwrap()
+}
+
+fn write_blob(name: &str, bytes: &[u8]) -> PathBuf {
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name);
+    fs::write(&path, bytes).unwrap();
+    path
+}
+
+const READ_FILE: &str = r#"
+static size_t read_file(const char *path, uint8_t *buffer, size_t capacity) {
+    FILE *file = fopen(path, "rb");
+    size_t size = fread(buffer, 1, capacity, file);
+    fclose(file);
+    return size;
+}
+"#;
+
+#[test]
+fn c_header_of_fixed_size_layouts() {
+    let header = to_c_header(&[Particle::get_layout(), Position::get_layout()]).unwrap();
+    assert!(header.contains("#pragma pack(push, 1)\ntypedef struct {\n    uint32_t id;\n"));
+    assert!(header.contains("_Static_assert(sizeof(Particle) == 32,"));
+    assert!(!header.contains("particle_free"));
+
+    let particle = Particle {
+        id: 7,
+        alive: true,
+        position: Position { x: 1.5, y: -2.0 },
+        mass: 0.25,
+        charge: -3,
+        spin: -300,
+        tick: u64::MAX,
+    };
+    let blob = write_blob(
+        "particle.bin",
+        &project_kind::bincode::serialize(&particle).unwrap(),
+    );
+    let program = format!(
+        r#"#include <stdio.h>
+#include "kind.h"
+{}
+int main(int argc, char **argv) {{
+    uint8_t buffer[64];
+    size_t size = read_file(argv[argc - 1], buffer, sizeof buffer), read;
+    Particle particle;
+    printf("%zu %zu %zu %zu %zu %zu %zu %zu %zu\n", sizeof(Particle),
+           offsetof(Particle, id), offsetof(Particle, alive), offsetof(Particle, position),
+           offsetof(Particle, position.y), offsetof(Particle, mass), offsetof(Particle, charge),
+           offsetof(Particle, spin), offsetof(Particle, tick));
+    if (particle_decode(buffer, size, &particle, &read) != 0) {{
+        return 1;
+    }}
+    printf("%zu %u %d %g %g %g %d %d %llu\n", read, particle.id, particle.alive,
+           particle.position.x, particle.position.y, particle.mass, particle.charge,
+           particle.spin, (unsigned long long)particle.tick);
+    printf("%d\n", particle_decode(buffer, size - 1, &particle, NULL));
+    return 0;
+}}
+"#,
+        READ_FILE
+    );
+
+    assert_eq!(
+        run_c("c_header_fixed", &header, &program, &[blob]),
+        "32 0 4 5 9 13 21 22 24\n\
+         32 7 1 1.5 -2 0.25 -3 -300 18446744073709551615\n\
+         -1\n"
+    );
+}
+
+#[test]
+fn c_header_decodes_variable_size_layouts() {
+    let header = to_c_header(&[Person::get_layout()]).unwrap();
+    let person = Person {
+        name: String::from("Zoë"),
+        age: 32,
+        position: Position { x: 0.5, y: 4.0 },
+        inventory: vec![
+            Item {
+                id: 1,
+                tags: vec![String::from("red"), String::from("")],
+            },
+            Item {
+                id: 2,
+                tags: vec![],
+            },
+        ],
+        grid: vec![vec![-1, 2], vec![], vec![i64::MIN]],
+        alive: true,
+    };
+    let bytes = project_kind::bincode::serialize(&person).unwrap();
+    let mut bad_bool = bytes.clone();
+    *bad_bool.last_mut().unwrap() = 2;
+    let mut bad_utf8 = bytes.clone();
+    bad_utf8[8 + 2] = 0xff;
+
+    let program = format!(
+        r#"#include <stdio.h>
+#include "kind.h"
+{}
+static void print_person(const Person *p) {{
+    uint64_t i, j;
+    printf("%.*s %u %g %g %d\n", (int)p->name.length, p->name.data, p->age, p->position.x,
+           p->position.y, p->alive);
+    for (i = 0; i < p->inventory.length; i++) {{
+        printf("item %u:", p->inventory.items[i].id);
+        for (j = 0; j < p->inventory.items[i].tags.length; j++) {{
+            printf(" '%.*s'", (int)p->inventory.items[i].tags.items[j].length,
+                   p->inventory.items[i].tags.items[j].data);
+        }}
+        printf("\n");
+    }}
+    for (i = 0; i < p->grid.length; i++) {{
+        printf("row:");
+        for (j = 0; j < p->grid.items[i].length; j++) {{
+            printf(" %lld", (long long)p->grid.items[i].items[j]);
+        }}
+        printf("\n");
+    }}
+}}
+
+int main(int argc, char **argv) {{
+    uint8_t buffer[256];
+    Person person;
+    size_t size, read, truncated;
+    int index;
+    for (index = 1; index < argc; index++) {{
+        size = read_file(argv[index], buffer, sizeof buffer);
+        if (person_decode(buffer, size, &person, &read) != 0) {{
+            printf("rejected\n");
+            continue;
+        }}
+        printf("read %zu of %zu\n", read, size);
+        print_person(&person);
+        person_free(&person);
+        for (truncated = 0; truncated < size; truncated++) {{
+            if (person_decode(buffer, truncated, &person, NULL) != -1) {{
+                printf("accepted %zu bytes\n", truncated);
+            }}
+        }}
+    }}
+    return 0;
+}}
+"#,
+        READ_FILE
+    );
+
+    let args = [
+        write_blob("person.bin", &bytes),
+        write_blob("person_bad_bool.bin", &bad_bool),
+        write_blob("person_bad_utf8.bin", &bad_utf8),
+    ];
+    assert_eq!(
+        run_c("c_header_variable", &header, &program, &args),
+        format!(
+            "read {0} of {0}\n\
+             Zoë 32 0.5 4 1\n\
+             item 1: 'red' ''\n\
+             item 2:\n\
+             row: -1 2\n\
+             row:\n\
+             row: -9223372036854775808\n\
+             rejected\n\
+             rejected\n",
+            bytes.len()
+        )
+    );
+}
+
+#[test]
+fn c_header_rejects_names_c_cannot_express() {
+    let mut person_layout = Person::get_layout();
+    person_layout.fields.as_mut().unwrap()[3]
+        .fields
+        .as_mut()
+        .unwrap()[0]
+        .fields
+        .as_mut()
+        .unwrap()[0]
+        .name = String::from("default");
+    assert_eq!(
+        to_c_header(&[person_layout]),
+        Err(Error::Identifier {
+            path: String::from("inventory.default"),
+            message: String::from("`default` is a C keyword"),
+        })
+    );
+
+    let layout = Layout {
+        name: String::from("player-state"),
+        kind: LayoutKind::U8,
+        fields: None,
+        default: None,
+        field_number: None,
+    };
+    assert_eq!(
+        to_c_header(&[layout]).unwrap_err().to_string(),
+        "invalid identifier: `player-state` is not a C identifier"
+    );
+    assert_eq!(
+        to_c_header(&[Position::get_layout(), Position::get_layout()])
+            .unwrap_err()
+            .to_string(),
+        "invalid identifier: duplicate name `Position`"
+    );
+}
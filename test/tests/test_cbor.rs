@@ -0,0 +1,131 @@
+use ciborium::Value;
+use project_kind::{HasLayout, Layout, LayoutKind};
+use project_kind_macros::component;
+
+#[component]
+#[derive(Debug, Clone, PartialEq)]
+struct Person {
+    age: u8,
+    height: i16,
+    weight: f32,
+    avatar: Vec<u8>,
+    favorite_names: Vec<String>,
+}
+
+fn person() -> Person {
+    Person {
+        age: 27,
+        height: -5,
+        weight: 70.5,
+        avatar: vec![1, 2, 3],
+        favorite_names: vec![String::from("Andy")],
+    }
+}
+
+#[test]
+fn serialize_deserialize_cbor() {
+    let person_layout = Person::get_layout();
+    let person_bytes = bincode::serialize(&person()).unwrap();
+
+    let person_cbor = project_kind::serialize_cbor(&person_layout, &person_bytes).unwrap();
+    let person_bytes_after = project_kind::deserialize_cbor(&person_layout, &person_cbor).unwrap();
+
+    assert_eq!(person_bytes, person_bytes_after);
+    let person_typed_after = bincode::deserialize::<Person>(&person_bytes_after).unwrap();
+    assert_eq!(person(), person_typed_after);
+}
+
+#[test]
+fn serialize_cbor_keeps_widths_and_binary() {
+    let person_layout = Person::get_layout();
+    let person_bytes = bincode::serialize(&person()).unwrap();
+
+    let person_cbor = project_kind::serialize_cbor(&person_layout, &person_bytes).unwrap();
+    assert_eq!(&person_cbor[..7], b"\xa5\x63age\x18\x1b");
+
+    let person_value: Value = ciborium::de::from_reader(&person_cbor[..]).unwrap();
+    let person_value_expected = Value::Map(vec![
+        (Value::from("age"), Value::from(27)),
+        (Value::from("height"), Value::from(-5)),
+        (Value::from("weight"), Value::from(70.5)),
+        (Value::from("avatar"), Value::Bytes(vec![1, 2, 3])),
+        (
+            Value::from("favorite_names"),
+            Value::Array(vec![Value::from("Andy")]),
+        ),
+    ]);
+    assert_eq!(person_value, person_value_expected);
+}
+
+#[test]
+fn deserialize_cbor_from_other_encoder() {
+    let person_layout = Person::get_layout();
+    let person_value = Value::Map(vec![
+        (Value::from("favorite_names"), Value::Array(vec![])),
+        (Value::from("avatar"), Value::Bytes(vec![9])),
+        (Value::from("weight"), Value::from(1.5)),
+        (Value::from("height"), Value::from(-300)),
+        (Value::from("age"), Value::from(3)),
+    ]);
+    let mut person_cbor = Vec::new();
+    ciborium::ser::into_writer(&person_value, &mut person_cbor).unwrap();
+
+    let person_bytes = project_kind::deserialize_cbor(&person_layout, &person_cbor).unwrap();
+    let person_typed = bincode::deserialize::<Person>(&person_bytes).unwrap();
+    let person_typed_expected = Person {
+        age: 3,
+        height: -300,
+        weight: 1.5,
+        avatar: vec![9],
+        favorite_names: vec![],
+    };
+    assert_eq!(person_typed, person_typed_expected);
+
+    let person_value = Value::Map(vec![(Value::from("age"), Value::from(256))]);
+    let mut person_cbor = Vec::new();
+    ciborium::ser::into_writer(&person_value, &mut person_cbor).unwrap();
+    let error = project_kind::deserialize_cbor(&person_layout, &person_cbor).unwrap_err();
+    assert!(error.to_string().contains("out of range for `age`"));
+}
+
+#[test]
+fn deserialize_cbor_rejects_hostile_maps() {
+    let layout = Layout {
+        name: String::from("Score"),
+        kind: LayoutKind::Struct,
+        fields: Some(vec![Layout {
+            name: String::from("points"),
+            kind: LayoutKind::U8,
+            fields: None,
+            default: None,
+            field_number: None,
+        }]),
+        default: None,
+        field_number: None,
+    };
+    // {"points": 1, "x": [[[...]]]} with the unknown value nested 2M deep.
+    let map = |depth: usize| {
+        let mut serial = b"\xa2\x66points\x01\x61x".to_vec();
+        serial.extend(std::iter::repeat_n(0x81, depth));
+        serial.push(0x00);
+        serial
+    };
+    assert_eq!(
+        project_kind::deserialize_cbor(&layout, &map(10)).unwrap(),
+        vec![1]
+    );
+    assert_eq!(
+        project_kind::deserialize_cbor(&layout, &map(2_000_000))
+            .unwrap_err()
+            .to_string(),
+        "cbor error: value nested deeper than 128 levels at byte 140"
+    );
+
+    let duplicate = b"\xa2\x66points\x01\x66points\x02";
+    assert_eq!(
+        project_kind::deserialize_cbor(&layout, duplicate)
+            .unwrap_err()
+            .to_string(),
+        "cbor error: duplicate field `points` in map at byte 9"
+    );
+}
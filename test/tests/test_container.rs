@@ -0,0 +1,176 @@
+use project_kind::{ContainerReader, ContainerWriter, Error, HasLayout, Layout, LayoutKind};
+use project_kind_macros::component;
+use serde_json::json;
+
+#[component(default)]
+#[derive(Debug, Clone, PartialEq)]
+struct Reading {
+    sensor: String,
+    values: Vec<i16>,
+}
+
+impl Default for Reading {
+    fn default() -> Self {
+        Reading {
+            sensor: String::from("unknown"),
+            values: vec![],
+        }
+    }
+}
+
+fn container() -> Vec<u8> {
+    let mut writer = ContainerWriter::new(Vec::new(), &Reading::get_layout()).unwrap();
+    for (sensor, values) in [("a", vec![1, -2]), ("b", vec![])] {
+        let reading = Reading {
+            sensor: String::from(sensor),
+            values,
+        };
+        writer
+            .write(&project_kind::bincode::serialize(&reading).unwrap())
+            .unwrap();
+    }
+    writer.finish().unwrap()
+}
+
+#[test]
+fn container_round_trip() {
+    let bytes = container();
+    assert_eq!(&bytes[..5], b"KCON\x01");
+
+    let reader = ContainerReader::new(bytes.as_slice()).unwrap();
+    // The stored layout keeps its defaults.
+    assert_eq!(reader.layout(), &Reading::get_layout());
+    let readings = reader
+        .map(|record| project_kind::bincode::deserialize::<Reading>(&record.unwrap()).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(readings[0].values, vec![1, -2]);
+    assert_eq!(readings[1].sensor, "b");
+    assert_eq!(readings.len(), 2);
+
+    let empty = ContainerWriter::new(Vec::new(), &Reading::get_layout())
+        .unwrap()
+        .finish()
+        .unwrap();
+    assert_eq!(
+        ContainerReader::new(empty.as_slice()).unwrap().to_json(),
+        Ok(json!([]))
+    );
+}
+
+#[test]
+fn container_to_json_without_the_type() {
+    // Only the bytes are needed; the layout comes from the header.
+    let value = ContainerReader::new(container().as_slice())
+        .unwrap()
+        .to_json()
+        .unwrap();
+    assert_eq!(
+        value,
+        json!([
+            {"sensor": "a", "values": [1, -2]},
+            {"sensor": "b", "values": []},
+        ])
+    );
+}
+
+#[test]
+fn container_rejects_damage() {
+    let mut writer = ContainerWriter::new(Vec::new(), &Reading::get_layout()).unwrap();
+    let mut record = project_kind::bincode::serialize(&Reading::default()).unwrap();
+    record.push(0);
+    assert_eq!(
+        writer.write(&record),
+        Err(Error::Container(String::from(
+            "1 trailing bytes after the record"
+        )))
+    );
+    assert!(writer.write(&record[..3]).is_err());
+    // Neither failed write reached the output.
+    let bytes = writer.finish().unwrap();
+    assert_eq!(ContainerReader::new(bytes.as_slice()).unwrap().count(), 0);
+
+    let error = |bytes: &[u8]| match ContainerReader::new(bytes) {
+        Ok(reader) => reader.to_json().unwrap_err().to_string(),
+        Err(error) => error.to_string(),
+    };
+    let bytes = container();
+    assert_eq!(error(b"KENV\x01"), "container error: not a container");
+    assert_eq!(
+        error(b"KCON\x02"),
+        "container error: unsupported container version 2"
+    );
+    assert_eq!(
+        error(&bytes[..20]),
+        "container error: container ends in a layout"
+    );
+    assert_eq!(
+        error(&bytes[..bytes.len() - 1]),
+        "container error: container ends in a record"
+    );
+    assert_eq!(
+        error(&bytes[..bytes.len() - 20]),
+        "container error: container ends in a record length"
+    );
+    let mut huge = bytes.clone();
+    huge.extend_from_slice(&u64::MAX.to_le_bytes());
+    assert_eq!(error(&huge), "container error: container ends in a record");
+
+    // A last record with junk after the value, as `write` would not allow.
+    let last = project_kind::bincode::serialize(&Reading {
+        sensor: String::from("b"),
+        values: vec![],
+    })
+    .unwrap();
+    let mut junk = bytes.clone();
+    let length_offset = junk.len() - last.len() - 8;
+    junk[length_offset..length_offset + 8].copy_from_slice(&(last.len() as u64 + 1).to_le_bytes());
+    junk.push(0);
+    assert_eq!(ContainerReader::new(junk.as_slice()).unwrap().count(), 2);
+    match ContainerReader::new(junk.as_slice()).unwrap().to_json() {
+        Err(Error::Limit { limit, max, .. }) => {
+            assert_eq!((limit.as_str(), max), ("max_trailing_bytes", 0))
+        }
+        result => panic!("unexpected result: {:?}", result),
+    }
+
+    let mut reader = ContainerReader::new(&bytes[..bytes.len() - 1]).unwrap();
+    assert!(reader.next().unwrap().is_ok());
+    assert!(reader.next().unwrap().is_err());
+    assert!(reader.next().is_none());
+}
+
+#[test]
+fn container_rejects_corrupt_layouts() {
+    let header = |layout: &str| {
+        let mut bytes = b"KCON\x01".to_vec();
+        bytes.extend_from_slice(&(layout.len() as u64).to_le_bytes());
+        bytes.extend_from_slice(layout.as_bytes());
+        // One record, which a corrupt layout must never get to decode.
+        bytes.extend_from_slice(&1u64.to_le_bytes());
+        bytes.push(0);
+        bytes
+    };
+    let error = |layout: &str| match ContainerReader::new(header(layout).as_slice()) {
+        Ok(_) => panic!("accepted {}", layout),
+        Err(error) => error.to_string(),
+    };
+    assert_eq!(
+        error(r#"{"name":"Bytes","kind":"Array","fields":[]}"#),
+        "container error: invalid layout: an array needs one element layout, found 0"
+    );
+    assert_eq!(
+        error(r#"{"name":"Person","kind":"Struct","fields":null}"#),
+        "container error: invalid layout: a struct needs a list of fields"
+    );
+    assert_eq!(
+        error(r#"{"name":"Person","kind":"Struct","fields":[{"name":"tags","kind":"Array"}]}"#),
+        "container error: invalid layout at `tags`: an array needs one element layout"
+    );
+    assert!(error("{").starts_with("container error: invalid layout: EOF"));
+
+    let valid = header(r#"{"name":"Flag","kind":"Bool"}"#);
+    assert_eq!(
+        ContainerReader::new(valid.as_slice()).unwrap().to_json(),
+        Ok(json!([false]))
+    );
+}
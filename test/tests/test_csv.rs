@@ -0,0 +1,105 @@
+use project_kind::{Error, HasLayout, Layout, LayoutKind};
+use project_kind_macros::component;
+
+#[component]
+#[derive(Debug, Clone, PartialEq)]
+struct Position {
+    x: f32,
+    y: f32,
+}
+
+#[component]
+#[derive(Debug, Clone, PartialEq)]
+struct Person {
+    name: String,
+    age: u8,
+    alive: bool,
+    pos: Position,
+}
+
+#[component]
+#[derive(Debug, Clone, PartialEq)]
+struct Team {
+    name: String,
+    members: Vec<String>,
+}
+
+fn person_array_layout() -> Layout {
+    Layout {
+        name: String::from("Persons"),
+        kind: LayoutKind::Array,
+        fields: Some(vec![Person::get_layout()]),
+        default: None,
+        field_number: None,
+    }
+}
+
+#[test]
+fn to_from_csv() {
+    let person_array_layout = person_array_layout();
+    let person_array_typed = vec![
+        Person {
+            name: String::from("Andy"),
+            age: 27,
+            alive: true,
+            pos: Position { x: 1.5, y: -2.0 },
+        },
+        Person {
+            name: String::from("Smith, Barbara"),
+            age: 50,
+            alive: false,
+            pos: Position { x: 0.0, y: 3.25 },
+        },
+    ];
+    let person_array_bytes = bincode::serialize(&person_array_typed).unwrap();
+
+    let person_array_csv = project_kind::to_csv(&person_array_layout, &person_array_bytes).unwrap();
+    let person_array_csv_expected = "name,age,alive,pos.x,pos.y
+Andy,27,true,1.5,-2
+\"Smith, Barbara\",50,false,0,3.25
+";
+    assert_eq!(person_array_csv, person_array_csv_expected);
+
+    let person_array_bytes_after =
+        project_kind::from_csv(&person_array_layout, &person_array_csv).unwrap();
+    assert_eq!(person_array_bytes, person_array_bytes_after);
+}
+
+#[test]
+fn from_csv_reports_row_and_column() {
+    let person_array_layout = person_array_layout();
+    let person_array_csv = "pos.y,pos.x,alive,age,name
+0,0,true,27,Andy
+0,0,true,300,Bob
+";
+    let error = project_kind::from_csv(&person_array_layout, person_array_csv).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "csv error at row 3 column 4: invalid U8 value `300` for `age`"
+    );
+    match error {
+        Error::Csv { row, column, .. } => {
+            assert_eq!(row, Some(3));
+            assert_eq!(column, Some(4));
+        }
+        error => panic!("unexpected error: {}", error),
+    }
+}
+
+#[test]
+fn csv_rejects_arrays() {
+    let team_array_layout = Layout {
+        name: String::from("Teams"),
+        kind: LayoutKind::Array,
+        fields: Some(vec![Team::get_layout()]),
+        default: None,
+        field_number: None,
+    };
+    let team_array_bytes = bincode::serialize(&Vec::<Team>::new()).unwrap();
+
+    let error = project_kind::to_csv(&team_array_layout, &team_array_bytes).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "csv error: field `members` is an array and cannot be flattened into csv columns"
+    );
+}
@@ -0,0 +1,100 @@
+use project_kind::{HasLayout, Layout, LayoutKind};
+use project_kind_macros::component;
+use serde_json::json;
+
+#[component]
+#[derive(Debug, Clone, PartialEq)]
+struct Position {
+    x: f32,
+    y: f32,
+}
+
+#[component]
+#[derive(Debug, Clone, PartialEq)]
+struct Person {
+    name: String,
+    age: u8,
+    alive: bool,
+    position: Position,
+    favorite_names: Vec<String>,
+}
+
+#[component(default)]
+#[derive(Debug, Clone, PartialEq)]
+struct Settings {
+    volume: f32,
+    title: String,
+    position: Position,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            volume: 0.5,
+            title: String::from("Untitled"),
+            position: Position { x: 1.0, y: 2.0 },
+        }
+    }
+}
+
+#[test]
+fn default_instance() {
+    let person_layout = Person::get_layout();
+
+    let person_string = person_layout.default_json();
+    let person_string_expected =
+        r#"{"age":0,"alive":false,"favorite_names":[],"name":"","position":{"x":0.0,"y":0.0}}"#;
+    assert_eq!(person_string, person_string_expected);
+
+    let person_typed =
+        bincode::deserialize::<Person>(&person_layout.default_bytes().unwrap()).unwrap();
+    let person_typed_expected = Person {
+        name: String::new(),
+        age: 0,
+        alive: false,
+        position: Position { x: 0.0, y: 0.0 },
+        favorite_names: vec![],
+    };
+    assert_eq!(person_typed, person_typed_expected);
+}
+
+#[test]
+fn default_overrides() {
+    let mut person_layout = Person::get_layout();
+    let fields = person_layout.fields.as_mut().unwrap();
+    fields[1].default = Some(json!(18));
+    fields[3].fields.as_mut().unwrap()[1].default = Some(json!(-1.5));
+
+    let person_typed =
+        bincode::deserialize::<Person>(&person_layout.default_bytes().unwrap()).unwrap();
+    assert_eq!(person_typed.age, 18);
+    assert_eq!(person_typed.position, Position { x: 0.0, y: -1.5 });
+}
+
+#[test]
+fn default_from_default_impl() {
+    let settings_layout = Settings::get_layout();
+    assert_eq!(
+        settings_layout.fields.as_ref().unwrap()[1].default,
+        Some(json!("Untitled"))
+    );
+
+    let settings_typed =
+        bincode::deserialize::<Settings>(&settings_layout.default_bytes().unwrap()).unwrap();
+    assert_eq!(settings_typed, Settings::default());
+}
+
+#[test]
+fn field_defaults_from_bytes() {
+    let position = Position { x: 3.0, y: 4.0 };
+    let layout = Position::get_layout()
+        .with_field_defaults(&bincode::serialize(&position).unwrap())
+        .unwrap();
+    assert_eq!(layout.fields.as_ref().unwrap()[1].default, Some(json!(4.0)));
+
+    // Bytes that do not decode are an error, not a panic.
+    let error = Position::get_layout()
+        .with_field_defaults(&[0, 0, 128])
+        .unwrap_err();
+    assert!(error.to_string().starts_with("decode error at byte 0"));
+}
@@ -0,0 +1,147 @@
+use project_kind::{Change, HasLayout, Layout, LayoutKind};
+use project_kind_macros::component;
+use serde_json::json;
+
+#[component]
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Item {
+    name: String,
+    count: u16,
+}
+
+#[component]
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Person {
+    name: String,
+    inventory: Vec<Item>,
+    age: u8,
+}
+
+#[component]
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Samples {
+    values: Vec<u32>,
+}
+
+fn item(name: &str, count: u16) -> Item {
+    Item {
+        name: String::from(name),
+        count,
+    }
+}
+
+#[test]
+fn diff_leaves() {
+    let person_layout = Person::get_layout();
+    let old = Person {
+        name: String::from("Andy"),
+        inventory: vec![item("Sword", 1)],
+        age: 27,
+    };
+    let new = Person {
+        name: String::from("Andy"),
+        inventory: vec![item("Sword", 2)],
+        age: 28,
+    };
+    let old_bytes = bincode::serialize(&old).unwrap();
+    let new_bytes = bincode::serialize(&new).unwrap();
+
+    let changes = project_kind::diff(&person_layout, &old_bytes, &new_bytes).unwrap();
+    let changes_expected = vec![
+        Change::Modified {
+            path: String::from("inventory[0].count"),
+            old: json!(1),
+            new: json!(2),
+        },
+        Change::Modified {
+            path: String::from("age"),
+            old: json!(27),
+            new: json!(28),
+        },
+    ];
+    assert_eq!(changes, changes_expected);
+    assert!(project_kind::diff(&person_layout, &old_bytes, &old_bytes)
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn diff_array_insertions_and_removals() {
+    let person_layout = Person::get_layout();
+    let old = Person {
+        name: String::from("Andy"),
+        inventory: vec![item("Sword", 1), item("Shield", 1), item("Arrow", 40)],
+        age: 27,
+    };
+    let new = Person {
+        name: String::from("Andy"),
+        inventory: vec![item("Potion", 3), item("Sword", 1), item("Arrow", 40)],
+        age: 27,
+    };
+    let old_bytes = bincode::serialize(&old).unwrap();
+    let new_bytes = bincode::serialize(&new).unwrap();
+
+    let changes = project_kind::diff(&person_layout, &old_bytes, &new_bytes).unwrap();
+    let printed = changes
+        .iter()
+        .map(|change| change.to_string())
+        .collect::<Vec<_>>();
+    let printed_expected = vec![
+        r#"+ inventory[0]: {"count":3,"name":"Potion"}"#,
+        r#"- inventory[1]: {"count":1,"name":"Shield"}"#,
+    ];
+    assert_eq!(printed, printed_expected);
+}
+
+#[test]
+fn diff_to_json() {
+    let person_layout = Person::get_layout();
+    let old = Person {
+        name: String::from("Andy"),
+        inventory: vec![],
+        age: 27,
+    };
+    let new = Person {
+        name: String::from("Bob"),
+        inventory: vec![item("Sword", 1)],
+        age: 27,
+    };
+    let old_bytes = bincode::serialize(&old).unwrap();
+    let new_bytes = bincode::serialize(&new).unwrap();
+
+    let changes = project_kind::diff(&person_layout, &old_bytes, &new_bytes).unwrap();
+    let changes_string = serde_json::to_string(&changes).unwrap();
+    let changes_string_expected = r#"[{"op":"modified","path":"name","old":"Andy","new":"Bob"},{"op":"inserted","path":"inventory[0]","value":{"count":1,"name":"Sword"}}]"#;
+    assert_eq!(changes_string, changes_string_expected);
+}
+
+#[test]
+fn diff_large_arrays() {
+    let layout = Samples::get_layout();
+    let bytes = |values: Vec<u32>| bincode::serialize(&Samples { values }).unwrap();
+    let old = (0..200_000).collect::<Vec<u32>>();
+
+    // A common prefix and suffix are matched without aligning the rest.
+    let mut inserted = old.clone();
+    inserted.insert(100_000, 7);
+    assert_eq!(
+        project_kind::diff(&layout, &bytes(old.clone()), &bytes(inserted)).unwrap(),
+        vec![Change::Inserted {
+            path: String::from("values[100000]"),
+            value: json!(7),
+        }]
+    );
+
+    // Too large a gap to align; elements are paired by index.
+    let shifted = (1..200_001).collect::<Vec<u32>>();
+    let changes = project_kind::diff(&layout, &bytes(old), &bytes(shifted)).unwrap();
+    assert_eq!(changes.len(), 200_000);
+    assert_eq!(
+        changes[199_999],
+        Change::Modified {
+            path: String::from("values[199999]"),
+            old: json!(199_999),
+            new: json!(200_000),
+        }
+    );
+}
@@ -0,0 +1,160 @@
+use project_kind::{
+    unwrap_with, wrap, wrap_layout, Envelope, Error, HasLayout, Layout, LayoutKind, Registry,
+};
+use project_kind_macros::component;
+use serde_json::json;
+
+#[component]
+#[derive(Debug, Clone, PartialEq)]
+struct Person {
+    name: String,
+    age: u8,
+}
+
+mod v2 {
+    use project_kind::{Layout, LayoutKind};
+    use project_kind_macros::component;
+
+    #[component]
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Person {
+        pub name: String,
+        pub age: u8,
+        pub email: String,
+    }
+}
+
+fn person_bytes() -> Vec<u8> {
+    project_kind::bincode::serialize(&Person {
+        name: String::from("Ann"),
+        age: 42,
+    })
+    .unwrap()
+}
+
+#[test]
+fn envelope_round_trip() {
+    let bytes = person_bytes();
+    let envelope = wrap::<Person>(&bytes).unwrap();
+    assert_eq!(&envelope[..6], b"KENV\x01\x01");
+    let parsed = Envelope::parse(&envelope).unwrap();
+    assert_eq!(
+        parsed,
+        Envelope {
+            name: "Person",
+            fingerprint: Person::get_layout().fingerprint(),
+            payload: &bytes,
+            checksummed: true,
+        }
+    );
+
+    let mut registry = Registry::new();
+    registry.register::<Person>().unwrap();
+    registry.register::<v2::Person>().unwrap();
+    assert_eq!(
+        unwrap_with(&registry, &envelope).unwrap(),
+        json!({"name": "Ann", "age": 42})
+    );
+
+    // Without a checksum the envelope is eight bytes shorter.
+    let plain = wrap_layout(&Person::get_layout(), &bytes, false).unwrap();
+    assert_eq!(plain.len(), envelope.len() - 8);
+    assert!(!Envelope::parse(&plain).unwrap().checksummed);
+    assert_eq!(
+        unwrap_with(&registry, &plain).unwrap(),
+        json!({"name": "Ann", "age": 42})
+    );
+}
+
+#[test]
+fn envelope_picks_the_version() {
+    let newer = v2::Person {
+        name: String::from("Bo"),
+        age: 7,
+        email: String::from("bo@example.com"),
+    };
+    let envelope = wrap::<v2::Person>(&project_kind::bincode::serialize(&newer).unwrap()).unwrap();
+
+    let mut registry = Registry::new();
+    registry.register::<Person>().unwrap();
+    let fingerprint = v2::Person::get_layout().fingerprint();
+    assert_eq!(
+        unwrap_with(&registry, &envelope),
+        Err(Error::Envelope(format!(
+            "no layout registered as `Person` with fingerprint {:016x}",
+            fingerprint
+        )))
+    );
+    registry.register::<v2::Person>().unwrap();
+    assert_eq!(
+        unwrap_with(&registry, &envelope).unwrap(),
+        json!({"name": "Bo", "age": 7, "email": "bo@example.com"})
+    );
+}
+
+#[test]
+fn envelope_rejects_damage() {
+    let bytes = person_bytes();
+    assert_eq!(
+        wrap::<Person>(&bytes[..bytes.len() - 1])
+            .unwrap_err()
+            .to_string(),
+        "decode error at byte 11 in `age`: truncated: needs 1 bytes, 0 remain"
+    );
+    let mut padded = bytes.clone();
+    padded.push(0);
+    assert_eq!(
+        wrap::<Person>(&padded),
+        Err(Error::Envelope(String::from(
+            "1 trailing bytes after the `Person` payload"
+        )))
+    );
+
+    let envelope = wrap::<Person>(&bytes).unwrap();
+    let parse_error = |bytes: &[u8]| Envelope::parse(bytes).unwrap_err().to_string();
+    let mut flipped = envelope.clone();
+    flipped[20] ^= 1;
+    assert_eq!(parse_error(&flipped), "envelope error: checksum mismatch");
+    assert_eq!(
+        parse_error(&envelope[..envelope.len() - 3]),
+        format!(
+            "envelope error: envelope ends in its checksum at byte {}",
+            envelope.len() - 3
+        )
+    );
+    assert_eq!(parse_error(b"JSON{}"), "envelope error: not an envelope");
+    assert_eq!(
+        parse_error(b"KENV\x02\x00"),
+        "envelope error: unsupported envelope version 2"
+    );
+    let mut huge = envelope[..6].to_vec();
+    huge.extend_from_slice(&u64::MAX.to_le_bytes());
+    assert_eq!(
+        parse_error(&huge),
+        "envelope error: envelope ends in its name at byte 14"
+    );
+    let mut trailing = envelope.clone();
+    trailing.push(0);
+    assert_eq!(
+        parse_error(&trailing),
+        "envelope error: 1 trailing bytes after the envelope"
+    );
+
+    // A well-formed envelope whose payload has junk after the value.
+    let mut registry = Registry::new();
+    registry.register::<Person>().unwrap();
+    let mut junk = wrap_layout(&Person::get_layout(), &bytes, false).unwrap();
+    let length_offset = junk.len() - bytes.len() - 8;
+    junk[length_offset..length_offset + 8].copy_from_slice(&(bytes.len() as u64 + 1).to_le_bytes());
+    junk.push(0);
+    assert_eq!(
+        Envelope::parse(&junk).unwrap().payload.len(),
+        bytes.len() + 1
+    );
+    match unwrap_with(&registry, &junk) {
+        Err(Error::Limit { limit, max, .. }) => {
+            assert_eq!((limit.as_str(), max), ("max_trailing_bytes", 0))
+        }
+        result => panic!("unexpected result: {:?}", result),
+    }
+}
@@ -0,0 +1,60 @@
+use project_kind::arbitrary::Unstructured;
+use project_kind::proptest::prelude::*;
+use project_kind::{
+    arbitrary_bytes, deserialize, json_strategy, round_trip_test, serialize, GenerateLimits,
+    HasLayout, Layout, LayoutKind,
+};
+use project_kind_macros::component;
+use serde_json::Value;
+
+#[component]
+#[derive(Debug, Clone, PartialEq)]
+struct Position {
+    x: f32,
+    y: f64,
+}
+
+#[component]
+#[derive(Debug, Clone, PartialEq)]
+struct Person {
+    name: String,
+    age: u8,
+    alive: bool,
+    score: u64,
+    position: Position,
+    favorite_names: Vec<String>,
+    inventory: Vec<u16>,
+}
+
+round_trip_test!(person_round_trip, Person);
+
+proptest! {
+    #[test]
+    fn generated_json_respects_limits(serial in json_strategy(
+        &Person::get_layout(),
+        &GenerateLimits { max_array_length: 3, max_string_length: 5 },
+    )) {
+        let person_layout = Person::get_layout();
+        let person_value = serde_json::from_str::<Value>(&serial).unwrap();
+        prop_assert!(person_value["name"].as_str().unwrap().chars().count() <= 5);
+        prop_assert!(person_value["favorite_names"].as_array().unwrap().len() <= 3);
+        prop_assert!(person_value["inventory"].as_array().unwrap().len() <= 3);
+
+        let person_bytes = deserialize(&person_layout, &serial).unwrap();
+        prop_assert_eq!(serialize(&person_layout, &person_bytes).unwrap(), serial);
+    }
+}
+
+#[test]
+fn arbitrary_bytes_decode() {
+    let person_layout = Person::get_layout();
+    let limits = GenerateLimits::default();
+    let input = (0..=255u8).cycle().take(4096).collect::<Vec<_>>();
+    let mut u = Unstructured::new(&input);
+    for _ in 0..8 {
+        let person_bytes = arbitrary_bytes(&person_layout, &mut u, &limits).unwrap();
+        let person_typed = bincode::deserialize::<Person>(&person_bytes).unwrap();
+        assert!(person_typed.favorite_names.len() <= limits.max_array_length);
+        assert_eq!(bincode::serialize(&person_typed).unwrap(), person_bytes);
+    }
+}
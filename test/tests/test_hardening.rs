@@ -0,0 +1,99 @@
+use project_kind::{DecodeLimits, Error, HasLayout, Layout, LayoutKind};
+use project_kind_macros::component;
+
+#[component]
+#[derive(Debug, Clone, PartialEq)]
+struct Item {
+    id: u16,
+    tags: Vec<String>,
+}
+
+#[component]
+#[derive(Debug, Clone, PartialEq)]
+struct Person {
+    name: String,
+    alive: bool,
+    inventory: Vec<Item>,
+}
+
+fn person_bytes() -> Vec<u8> {
+    bincode::serialize(&Person {
+        name: String::from("Bob"),
+        alive: true,
+        inventory: vec![Item {
+            id: 7,
+            tags: vec![String::from("sword")],
+        }],
+    })
+    .unwrap()
+}
+
+#[test]
+fn invalid_bool_and_utf8() {
+    let person_layout = Person::get_layout();
+
+    let mut person_bytes_bad_bool = person_bytes();
+    person_bytes_bad_bool[11] = 2;
+    match project_kind::serialize(&person_layout, &person_bytes_bad_bool).unwrap_err() {
+        Error::Decode {
+            offset,
+            path,
+            message,
+        } => {
+            assert_eq!(offset, 11);
+            assert_eq!(path, "alive");
+            assert_eq!(message, "invalid bool byte 0x02");
+        }
+        error => panic!("unexpected error: {}", error),
+    }
+
+    let mut person_bytes_bad_utf8 = person_bytes();
+    person_bytes_bad_utf8[9] = 0xff;
+    let error = project_kind::serialize(&person_layout, &person_bytes_bad_utf8).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "decode error at byte 9 in `name`: invalid utf-8 in string"
+    );
+}
+
+#[test]
+fn corrupt_length_prefixes() {
+    let person_layout = Person::get_layout();
+    let unlimited = DecodeLimits::unlimited();
+
+    let mut person_bytes_long_name = person_bytes();
+    person_bytes_long_name[..8].copy_from_slice(&u64::MAX.to_le_bytes());
+    let error =
+        project_kind::serialize_with_limits(&person_layout, &person_bytes_long_name, &unlimited)
+            .unwrap_err();
+    assert!(matches!(error, Error::Decode { offset: 8, .. }));
+
+    let mut person_bytes_many_items = person_bytes();
+    person_bytes_many_items[12..20].copy_from_slice(&(1u64 << 40).to_le_bytes());
+    let error =
+        project_kind::serialize_with_limits(&person_layout, &person_bytes_many_items, &unlimited)
+            .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "decode error at byte 12 in `inventory`: 1099511627776 elements cannot fit in the 23 bytes that remain"
+    );
+
+    for bytes in [&person_bytes_long_name, &person_bytes_many_items] {
+        assert!(project_kind::serialize_yaml(&person_layout, bytes).is_err());
+        assert!(project_kind::serialize_msgpack(&person_layout, bytes).is_err());
+        assert!(project_kind::serialize_cbor(&person_layout, bytes).is_err());
+        assert!(project_kind::get_path(&person_layout, bytes, "alive").is_err());
+        assert!(project_kind::diff(&person_layout, bytes, bytes).is_err());
+    }
+}
+
+#[test]
+fn truncated_blobs() {
+    let person_layout = Person::get_layout();
+    let person_bytes = person_bytes();
+    assert!(project_kind::serialize(&person_layout, &person_bytes).is_ok());
+    for length in 0..person_bytes.len() {
+        let error = project_kind::serialize(&person_layout, &person_bytes[..length]).unwrap_err();
+        assert!(matches!(error, Error::Decode { .. }), "{}", error);
+    }
+}
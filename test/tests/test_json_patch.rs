@@ -0,0 +1,221 @@
+use project_kind::{HasLayout, Layout, LayoutKind, Patch};
+use project_kind_macros::component;
+
+#[component]
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Item {
+    name: String,
+    count: u16,
+}
+
+#[component]
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Person {
+    name: String,
+    inventory: Vec<Item>,
+    age: u8,
+}
+
+#[component]
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Blob {
+    data: Vec<u8>,
+}
+
+#[component]
+#[derive(Debug, Clone, PartialEq)]
+struct Car {
+    name: String,
+    speed: f32,
+}
+
+fn item(name: &str, count: u16) -> Item {
+    Item {
+        name: String::from(name),
+        count,
+    }
+}
+
+fn person() -> Person {
+    Person {
+        name: String::from("Andy"),
+        inventory: vec![item("Sword", 1), item("Arrow", 40)],
+        age: 27,
+    }
+}
+
+#[test]
+fn apply_json_patch() {
+    let person_layout = Person::get_layout();
+    let person_bytes = bincode::serialize(&person()).unwrap();
+    let patch = serde_json::from_str::<Patch>(
+        r#"[
+            { "op": "test", "path": "/name", "value": "Andy" },
+            { "op": "replace", "path": "/inventory/1/count", "value": 39 },
+            { "op": "add", "path": "/inventory/-", "value": { "name": "Bow", "count": 1 } },
+            { "op": "move", "from": "/inventory/0", "path": "/inventory/-" },
+            { "op": "copy", "from": "/inventory/0/name", "path": "/name" },
+            { "op": "remove", "path": "/inventory/1" }
+        ]"#,
+    )
+    .unwrap();
+
+    let person_bytes_after =
+        project_kind::apply_json_patch(&person_layout, &person_bytes, &patch).unwrap();
+    let person_typed_after = bincode::deserialize::<Person>(&person_bytes_after).unwrap();
+    let person_typed_expected = Person {
+        name: String::from("Arrow"),
+        inventory: vec![item("Arrow", 39), item("Sword", 1)],
+        age: 27,
+    };
+    assert_eq!(person_typed_after, person_typed_expected);
+}
+
+#[test]
+fn apply_json_patch_validates_and_rolls_back() {
+    let person_layout = Person::get_layout();
+    let person_bytes = bincode::serialize(&person()).unwrap();
+
+    let invalid_patches = [
+        (
+            r#"[{ "op": "replace", "path": "/age", "value": 28 }, { "op": "replace", "path": "/age", "value": "old" }]"#,
+            1,
+        ),
+        (r#"[{ "op": "add", "path": "/height", "value": 180 }]"#, 0),
+        (r#"[{ "op": "remove", "path": "/name" }]"#, 0),
+        (
+            r#"[{ "op": "replace", "path": "/inventory/2/count", "value": 1 }]"#,
+            0,
+        ),
+        (
+            r#"[{ "op": "add", "path": "/inventory/0", "value": { "name": "Bow" } }]"#,
+            0,
+        ),
+        (r#"[{ "op": "replace", "path": "/age", "value": 300 }]"#, 0),
+        (r#"[{ "op": "test", "path": "/age", "value": 30 }]"#, 0),
+    ];
+    for (patch_string, failing_index) in invalid_patches.iter() {
+        let patch = serde_json::from_str::<Patch>(patch_string).unwrap();
+        match project_kind::apply_json_patch(&person_layout, &person_bytes, &patch) {
+            Err(project_kind::Error::Patch { index, .. }) => assert_eq!(index, *failing_index),
+            result => panic!("unexpected result for {}: {:?}", patch_string, result),
+        }
+    }
+}
+
+#[test]
+fn apply_json_patch_checks_f32_range() {
+    let car_layout = Car::get_layout();
+    let car = Car {
+        name: String::from("Beetle"),
+        speed: 1.0,
+    };
+    let car_bytes = bincode::serialize(&car).unwrap();
+
+    let patch =
+        serde_json::from_str::<Patch>(r#"[{ "op": "replace", "path": "/speed", "value": 1e300 }]"#)
+            .unwrap();
+    match project_kind::apply_json_patch(&car_layout, &car_bytes, &patch) {
+        Err(project_kind::Error::Patch { index, message }) => {
+            assert_eq!(index, 0);
+            assert!(
+                message.contains("1e300 is out of range for F32"),
+                "{}",
+                message
+            );
+        }
+        result => panic!("unexpected result: {:?}", result),
+    }
+
+    let patch = serde_json::from_str::<Patch>(
+        r#"[{ "op": "replace", "path": "/speed", "value": 3.4028235e38 }]"#,
+    )
+    .unwrap();
+    let car_bytes_after = project_kind::apply_json_patch(&car_layout, &car_bytes, &patch).unwrap();
+    let car_typed_after = bincode::deserialize::<Car>(&car_bytes_after).unwrap();
+    assert_eq!(car_typed_after.speed, f32::MAX);
+}
+
+#[test]
+fn apply_json_patch_tests_numbers_by_value() {
+    let car_layout = Car::get_layout();
+    let car = Car {
+        name: String::from("Beetle"),
+        speed: 1.0,
+    };
+    let car_bytes = bincode::serialize(&car).unwrap();
+
+    let patch = serde_json::from_str::<Patch>(
+        r#"[
+            { "op": "test", "path": "/speed", "value": 1 },
+            { "op": "test", "path": "/speed", "value": 1.0 },
+            { "op": "test", "path": "", "value": { "name": "Beetle", "speed": 1 } }
+        ]"#,
+    )
+    .unwrap();
+    let car_bytes_after = project_kind::apply_json_patch(&car_layout, &car_bytes, &patch).unwrap();
+    assert_eq!(car_bytes_after, car_bytes);
+
+    for value in ["1.5", "\"1\"", "null"] {
+        let patch_string = format!(
+            r#"[{{ "op": "test", "path": "/speed", "value": {} }}]"#,
+            value
+        );
+        let patch = serde_json::from_str::<Patch>(&patch_string).unwrap();
+        match project_kind::apply_json_patch(&car_layout, &car_bytes, &patch) {
+            Err(project_kind::Error::Patch { index, .. }) => assert_eq!(index, 0),
+            result => panic!("unexpected result for {}: {:?}", value, result),
+        }
+    }
+}
+
+#[test]
+fn make_json_patch() {
+    let person_layout = Person::get_layout();
+    let old = person();
+    let new = Person {
+        name: String::from("Andy"),
+        inventory: vec![item("Potion", 2), item("Arrow", 38), item("Bow", 1)],
+        age: 28,
+    };
+    let old_bytes = bincode::serialize(&old).unwrap();
+    let new_bytes = bincode::serialize(&new).unwrap();
+
+    let patch = project_kind::make_json_patch(&person_layout, &old_bytes, &new_bytes).unwrap();
+
+    let new_bytes_after =
+        project_kind::apply_json_patch(&person_layout, &old_bytes, &patch).unwrap();
+    assert_eq!(new_bytes, new_bytes_after);
+
+    let patch_string_expected = r#"[{"op":"replace","path":"/inventory/0/name","value":"Potion"},{"op":"replace","path":"/inventory/0/count","value":2},{"op":"replace","path":"/inventory/1/count","value":38},{"op":"add","path":"/inventory/2","value":{"count":1,"name":"Bow"}},{"op":"replace","path":"/age","value":28}]"#;
+    assert_eq!(
+        serde_json::to_string(&patch).unwrap(),
+        patch_string_expected
+    );
+}
+
+#[test]
+fn make_json_patch_large_arrays() {
+    let layout = Blob::get_layout();
+    let bytes = |data: Vec<u8>| bincode::serialize(&Blob { data }).unwrap();
+    let old = (0..200_000).map(|i| (i % 251) as u8).collect::<Vec<_>>();
+
+    let mut inserted = old.clone();
+    inserted.insert(100_000, 7);
+    let patch =
+        project_kind::make_json_patch(&layout, &bytes(old.clone()), &bytes(inserted.clone()))
+            .unwrap();
+    assert_eq!(
+        serde_json::to_string(&patch).unwrap(),
+        r#"[{"op":"add","path":"/data/100000","value":7}]"#
+    );
+    assert_eq!(
+        project_kind::apply_json_patch(&layout, &bytes(old.clone()), &patch).unwrap(),
+        bytes(inserted)
+    );
+
+    // Too large a gap to align; elements are replaced in place.
+    let shifted = old.iter().map(|value| value ^ 1).collect::<Vec<_>>();
+    let patch = project_kind::make_json_patch(&layout, &bytes(old), &bytes(shifted)).unwrap();
+    assert_eq!(patch.0.len(), 200_000);
+}
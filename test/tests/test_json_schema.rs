@@ -0,0 +1,114 @@
+use project_kind::{HasLayout, Layout, LayoutKind};
+use project_kind_macros::component;
+use serde_json::{json, Value};
+
+#[component]
+#[derive(Debug, Clone, PartialEq)]
+struct Position {
+    x: f32,
+    y: f32,
+}
+
+#[component]
+#[derive(Debug, Clone, PartialEq)]
+struct Person {
+    name: String,
+    age: u8,
+    alive: bool,
+    balance: i64,
+    position: Position,
+    favorite_numbers: Vec<u16>,
+}
+
+fn person() -> Person {
+    Person {
+        name: String::from("Bob"),
+        age: 32,
+        alive: true,
+        balance: -12,
+        position: Position { x: 1.5, y: -2.0 },
+        favorite_numbers: vec![7, 42],
+    }
+}
+
+#[test]
+fn json_schema_of_primitives() {
+    let age_layout = Layout {
+        name: String::from("age"),
+        kind: LayoutKind::U8,
+        fields: None,
+        default: Some(json!(18)),
+        field_number: None,
+    };
+    assert_eq!(
+        age_layout.to_json_schema(),
+        json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "title": "age",
+            "type": "integer",
+            "minimum": 0,
+            "maximum": 255,
+            "default": 18,
+        })
+    );
+
+    let balance_schema = &Person::get_layout().to_json_schema()["properties"]["balance"];
+    assert_eq!(balance_schema["minimum"], json!(i64::MIN));
+    assert_eq!(balance_schema["maximum"], json!(i64::MAX));
+}
+
+#[test]
+fn json_schema_of_structs_and_arrays() {
+    let person_schema = Person::get_layout().to_json_schema();
+    assert_eq!(person_schema["title"], json!("Person"));
+    assert_eq!(person_schema["type"], json!("object"));
+    assert_eq!(
+        person_schema["required"],
+        json!([
+            "name",
+            "age",
+            "alive",
+            "balance",
+            "position",
+            "favorite_numbers"
+        ])
+    );
+    assert_eq!(person_schema["additionalProperties"], json!(false));
+    assert_eq!(
+        person_schema["properties"]["position"]["required"],
+        json!(["x", "y"])
+    );
+    assert_eq!(
+        person_schema["properties"]["favorite_numbers"],
+        json!({
+            "type": "array",
+            "items": { "type": "integer", "minimum": 0, "maximum": 65535 },
+        })
+    );
+}
+
+#[test]
+fn json_schema_validates_serialized_documents() {
+    let person_layout = Person::get_layout();
+    let validator = jsonschema::validator_for(&person_layout.to_json_schema()).unwrap();
+
+    let person_bytes = bincode::serialize(&person()).unwrap();
+    let person_string = project_kind::serialize(&person_layout, &person_bytes).unwrap();
+    let person_value = serde_json::from_str::<Value>(&person_string).unwrap();
+    assert!(validator.is_valid(&person_value));
+
+    let mut person_value_bad_age = person_value.clone();
+    person_value_bad_age["age"] = json!(300);
+    assert!(!validator.is_valid(&person_value_bad_age));
+
+    let mut person_value_missing = person_value.clone();
+    person_value_missing["position"]
+        .as_object_mut()
+        .unwrap()
+        .remove("y");
+    assert!(!validator.is_valid(&person_value_missing));
+
+    let mut person_value_extra = person_value;
+    person_value_extra["height"] = json!(180);
+    assert!(!validator.is_valid(&person_value_extra));
+}
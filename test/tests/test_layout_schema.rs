@@ -0,0 +1,34 @@
+use project_kind::{HasLayout, Layout, LayoutKind};
+use project_kind_macros::component;
+
+#[component]
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Person {
+    name: String,
+    favorite_numbers: Vec<u8>,
+}
+
+#[test]
+fn serialize_layout() {
+    let person_layout = Person::get_layout();
+    let person_layout_string = serde_json::to_string(&person_layout).unwrap();
+    let person_layout_string_expected = r#"{"name":"Person","kind":"Struct","fields":[{"name":"name","kind":"String","fields":null},{"name":"favorite_numbers","kind":"Array","fields":[{"name":"unnamed","kind":"U8","fields":null}]}]}"#;
+    assert_eq!(person_layout_string, person_layout_string_expected);
+
+    let person_layout_after = serde_json::from_str::<Layout>(&person_layout_string).unwrap();
+    assert_eq!(person_layout, person_layout_after);
+}
+
+#[test]
+fn deserialize_layout_without_primitive_fields() {
+    let person_layout_string = r#"{
+        "name": "Person",
+        "kind": "Struct",
+        "fields": [
+            { "name": "name", "kind": "String" },
+            { "name": "favorite_numbers", "kind": "Array", "fields": [{ "name": "unnamed", "kind": "U8" }] }
+        ]
+    }"#;
+    let person_layout = serde_json::from_str::<Layout>(person_layout_string).unwrap();
+    assert_eq!(person_layout, Person::get_layout());
+}
@@ -0,0 +1,221 @@
+use project_kind::{
+    ContainerReader, ContainerWriter, DecodeLimits, Error, HasLayout, Layout, LayoutBytes,
+    LayoutKind, Registry,
+};
+use project_kind_macros::component;
+
+#[component]
+#[derive(Debug, Clone, PartialEq)]
+struct Position {
+    x: u8,
+    y: u8,
+}
+
+#[component]
+#[derive(Debug, Clone, PartialEq)]
+struct Person {
+    name: String,
+    position: Position,
+    inventory: Vec<u8>,
+}
+
+#[component]
+#[derive(Debug, Clone, PartialEq)]
+struct Blob {
+    data: Vec<u8>,
+}
+
+fn person_bytes() -> Vec<u8> {
+    bincode::serialize(&Person {
+        name: String::from("Robert"),
+        position: Position { x: 1, y: 2 },
+        inventory: vec![1, 2, 3],
+    })
+    .unwrap()
+}
+
+fn limit_of(error: Error) -> (String, String, usize) {
+    match error {
+        Error::Limit {
+            path, limit, max, ..
+        } => (path, limit, max),
+        error => panic!("unexpected error: {}", error),
+    }
+}
+
+#[test]
+fn array_length_limits() {
+    let person_layout = Person::get_layout();
+    let limits = DecodeLimits {
+        max_array_length: 2,
+        ..DecodeLimits::default()
+    };
+    let error =
+        project_kind::serialize_with_limits(&person_layout, &person_bytes(), &limits).unwrap_err();
+    assert_eq!(
+        limit_of(error),
+        (
+            String::from("inventory"),
+            String::from("max_array_length"),
+            2
+        )
+    );
+
+    // Elements that take no bytes cannot be bounded by the blob's size.
+    let markers_layout = Layout {
+        name: String::from("markers"),
+        kind: LayoutKind::Array,
+        fields: Some(vec![Layout {
+            name: String::from("Marker"),
+            kind: LayoutKind::Struct,
+            fields: Some(vec![]),
+            default: None,
+            field_number: None,
+        }]),
+        default: None,
+        field_number: None,
+    };
+    let markers_bytes = u64::MAX.to_le_bytes();
+    let error = project_kind::serialize_with_limits(
+        &markers_layout,
+        &markers_bytes,
+        &DecodeLimits::default(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "max_array_length of 1048576 exceeded at byte 0"
+    );
+}
+
+#[test]
+fn depth_limits() {
+    let person_layout = Person::get_layout();
+    let limits = DecodeLimits {
+        max_depth: 1,
+        ..DecodeLimits::default()
+    };
+    let error =
+        project_kind::serialize_with_limits(&person_layout, &person_bytes(), &limits).unwrap_err();
+    assert_eq!(
+        limit_of(error),
+        (String::from("position.x"), String::from("max_depth"), 1)
+    );
+
+    let mut nested_layout = Layout {
+        name: String::from("value"),
+        kind: LayoutKind::U8,
+        fields: None,
+        default: None,
+        field_number: None,
+    };
+    for _ in 0..100_000 {
+        nested_layout = Layout {
+            name: String::from("inner"),
+            kind: LayoutKind::Struct,
+            fields: Some(vec![nested_layout]),
+            default: None,
+            field_number: None,
+        };
+    }
+    let error = project_kind::serialize_with_limits(&nested_layout, &[7], &DecodeLimits::default())
+        .unwrap_err();
+    assert_eq!(limit_of(error).1, "max_depth");
+    // Dropping a layout this deep would itself overflow the stack.
+    std::mem::forget(nested_layout);
+}
+
+#[test]
+fn string_and_output_limits() {
+    let person_layout = Person::get_layout();
+    let person_bytes = person_bytes();
+    let person_string = project_kind::serialize(&person_layout, &person_bytes).unwrap();
+
+    let limits = DecodeLimits {
+        max_string_bytes: 4,
+        ..DecodeLimits::default()
+    };
+    let error =
+        project_kind::serialize_with_limits(&person_layout, &person_bytes, &limits).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "max_string_bytes of 4 exceeded at byte 0 in `name`"
+    );
+
+    let limits = DecodeLimits {
+        max_output_size: person_string.len(),
+        ..DecodeLimits::default()
+    };
+    let person_string_limited =
+        project_kind::serialize_with_limits(&person_layout, &person_bytes, &limits).unwrap();
+    assert_eq!(person_string_limited, person_string);
+
+    let limits = DecodeLimits {
+        max_output_size: person_string.len() - 1,
+        ..DecodeLimits::default()
+    };
+    let error =
+        project_kind::serialize_with_limits(&person_layout, &person_bytes, &limits).unwrap_err();
+    assert_eq!(limit_of(error).1, "max_output_size");
+}
+
+#[test]
+fn large_values_without_limits() {
+    // One element over the default `max_array_length`.
+    let blob_layout = Blob::get_layout();
+    let blob = Blob {
+        data: vec![7; (1 << 20) + 1],
+    };
+    let blob_bytes = bincode::serialize(&blob).unwrap();
+
+    let blob_string = project_kind::serialize(&blob_layout, &blob_bytes).unwrap();
+    assert_eq!(
+        serde_json::to_string(&LayoutBytes(&blob_layout, &blob_bytes)).unwrap(),
+        blob_string
+    );
+    assert!(project_kind::serialize_msgpack(&blob_layout, &blob_bytes).is_ok());
+    assert!(project_kind::serialize_cbor(&blob_layout, &blob_bytes).is_ok());
+    assert!(project_kind::serialize_yaml(&blob_layout, &blob_bytes).is_ok());
+    assert_eq!(
+        project_kind::get_path(&blob_layout, &blob_bytes, "data[1048576]").unwrap(),
+        serde_json::json!(7)
+    );
+    let mut blob_bytes_after = blob_bytes.clone();
+    project_kind::set_path(&blob_layout, &mut blob_bytes_after, "data[0]", 8.into()).unwrap();
+    assert_eq!(
+        project_kind::diff(&blob_layout, &blob_bytes, &blob_bytes_after)
+            .unwrap()
+            .len(),
+        1
+    );
+
+    // Writers take the value; readers of untrusted input choose the limits.
+    let envelope = project_kind::wrap::<Blob>(&blob_bytes).unwrap();
+    let mut registry = Registry::new();
+    registry.register::<Blob>().unwrap();
+    let error = project_kind::unwrap_with(&registry, &envelope).unwrap_err();
+    assert_eq!(limit_of(error).1, "max_array_length");
+    let unlimited = DecodeLimits::unlimited();
+    assert!(project_kind::unwrap_with_limits(&registry, &envelope, &unlimited).is_ok());
+
+    let mut writer = ContainerWriter::new(Vec::new(), &blob_layout).unwrap();
+    writer.write(&blob_bytes).unwrap();
+    let container = writer.finish().unwrap();
+    let reader = ContainerReader::new(container.as_slice()).unwrap();
+    assert!(reader.to_json_with_limits(&unlimited).is_ok());
+
+    let limits = DecodeLimits::default();
+    let error =
+        project_kind::serialize_with_limits(&blob_layout, &blob_bytes, &limits).unwrap_err();
+    assert_eq!(
+        limit_of(error),
+        (
+            String::from("data"),
+            String::from("max_array_length"),
+            1 << 20
+        )
+    );
+    let error = project_kind::serialize_msgpack_with_limits(&blob_layout, &blob_bytes, &limits)
+        .unwrap_err();
+    assert_eq!(limit_of(error).1, "max_array_length");
+}
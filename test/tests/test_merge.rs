@@ -0,0 +1,96 @@
+use project_kind::{HasLayout, Layout, LayoutKind};
+use project_kind_macros::component;
+
+#[component]
+#[derive(Debug, Clone, PartialEq)]
+struct Graphics {
+    width: u32,
+    height: u32,
+    fullscreen: bool,
+}
+
+#[component]
+#[derive(Debug, Clone, PartialEq)]
+struct Config {
+    name: String,
+    graphics: Graphics,
+    volume: f32,
+    mods: Vec<String>,
+}
+
+fn config() -> Config {
+    Config {
+        name: String::from("default"),
+        graphics: Graphics {
+            width: 1920,
+            height: 1080,
+            fullscreen: false,
+        },
+        volume: 0.5,
+        mods: vec![String::from("base"), String::from("extras")],
+    }
+}
+
+#[test]
+fn deserialize_merge_overrides_present_fields() {
+    let config_layout = Config::get_layout();
+    let config_bytes = bincode::serialize(&config()).unwrap();
+
+    let partial = r#"{"graphics":{"fullscreen":true},"volume":0.75}"#;
+    let config_bytes_after =
+        project_kind::deserialize_merge(&config_layout, &config_bytes, partial).unwrap();
+    let config_typed_after = bincode::deserialize::<Config>(&config_bytes_after).unwrap();
+
+    let mut config_typed_expected = config();
+    config_typed_expected.graphics.fullscreen = true;
+    config_typed_expected.volume = 0.75;
+    assert_eq!(config_typed_after, config_typed_expected);
+}
+
+#[test]
+fn deserialize_merge_replaces_arrays() {
+    let config_layout = Config::get_layout();
+    let config_bytes = bincode::serialize(&config()).unwrap();
+
+    let partial = r#"{"mods":["hardcore"]}"#;
+    let config_bytes_after =
+        project_kind::deserialize_merge(&config_layout, &config_bytes, partial).unwrap();
+    let config_typed_after = bincode::deserialize::<Config>(&config_bytes_after).unwrap();
+
+    let mut config_typed_expected = config();
+    config_typed_expected.mods = vec![String::from("hardcore")];
+    assert_eq!(config_typed_after, config_typed_expected);
+}
+
+#[test]
+fn deserialize_merge_empty_keeps_base() {
+    let config_layout = Config::get_layout();
+    let config_bytes = bincode::serialize(&config()).unwrap();
+
+    let config_bytes_after =
+        project_kind::deserialize_merge(&config_layout, &config_bytes, "{}").unwrap();
+    assert_eq!(config_bytes, config_bytes_after);
+}
+
+#[test]
+fn deserialize_merge_rejects_unknown_fields() {
+    let config_layout = Config::get_layout();
+    let config_bytes = bincode::serialize(&config()).unwrap();
+
+    for (partial, path_expected, message_expected) in [
+        (r#"{"sped":5}"#, "sped", "unknown field `sped`"),
+        (
+            r#"{"volume":0.75,"graphics":{"fulscreen":true}}"#,
+            "graphics.fulscreen",
+            "unknown field `fulscreen`",
+        ),
+    ] {
+        match project_kind::deserialize_merge(&config_layout, &config_bytes, partial) {
+            Err(project_kind::Error::Value { path, message }) => {
+                assert_eq!(path, path_expected);
+                assert_eq!(message, message_expected);
+            }
+            result => panic!("unexpected result for {}: {:?}", partial, result),
+        }
+    }
+}
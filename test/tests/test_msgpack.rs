@@ -0,0 +1,65 @@
+use project_kind::{HasLayout, Layout, LayoutKind};
+use project_kind_macros::component;
+
+#[component]
+#[derive(Debug, Clone, PartialEq)]
+struct Person {
+    age: u8,
+    height: i16,
+    weight: f32,
+    avatar: Vec<u8>,
+    favorite_names: Vec<String>,
+}
+
+fn person() -> Person {
+    Person {
+        age: 27,
+        height: -5,
+        weight: 70.5,
+        avatar: vec![1, 2, 3],
+        favorite_names: vec![String::from("Andy")],
+    }
+}
+
+#[test]
+fn serialize_deserialize_msgpack() {
+    let person_layout = Person::get_layout();
+    let person_bytes = bincode::serialize(&person()).unwrap();
+
+    let person_msgpack = project_kind::serialize_msgpack(&person_layout, &person_bytes).unwrap();
+    let person_bytes_after =
+        project_kind::deserialize_msgpack(&person_layout, &person_msgpack).unwrap();
+
+    assert_eq!(person_bytes, person_bytes_after);
+    let person_typed_after = bincode::deserialize::<Person>(&person_bytes_after).unwrap();
+    assert_eq!(person(), person_typed_after);
+}
+
+#[test]
+fn serialize_msgpack_keeps_widths_and_binary() {
+    let person_layout = Person::get_layout();
+    let person_bytes = bincode::serialize(&person()).unwrap();
+
+    let person_msgpack = project_kind::serialize_msgpack(&person_layout, &person_bytes).unwrap();
+
+    let mut person_msgpack_expected = vec![0x85];
+    person_msgpack_expected.extend_from_slice(b"\xa3age\xcc\x1b");
+    person_msgpack_expected.extend_from_slice(b"\xa6height\xd1\xff\xfb");
+    person_msgpack_expected.extend_from_slice(b"\xa6weight\xca\x42\x8d\x00\x00");
+    person_msgpack_expected.extend_from_slice(b"\xa6avatar\xc4\x03\x01\x02\x03");
+    person_msgpack_expected.extend_from_slice(b"\xaefavorite_names\x91\xa4Andy");
+    assert_eq!(person_msgpack, person_msgpack_expected);
+}
+
+#[test]
+fn deserialize_msgpack_rejects_out_of_range() {
+    let person_layout = Person::get_layout();
+    let mut person_msgpack = vec![0x85];
+    person_msgpack.extend_from_slice(b"\xa3age\xcd\x01\x2c");
+    person_msgpack.extend_from_slice(b"\xa6height\x00");
+    person_msgpack.extend_from_slice(b"\xa6weight\x00");
+    person_msgpack.extend_from_slice(b"\xa6avatar\xc4\x00");
+    person_msgpack.extend_from_slice(b"\xaefavorite_names\x90");
+
+    assert!(project_kind::deserialize_msgpack(&person_layout, &person_msgpack).is_err());
+}
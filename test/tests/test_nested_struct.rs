@@ -0,0 +1,65 @@
+use project_kind::{HasLayout, Layout, LayoutKind};
+use project_kind_macros::component;
+
+#[component]
+#[derive(Debug, Clone, PartialEq)]
+struct Position {
+    x: f32,
+    y: f32,
+}
+
+#[component]
+#[derive(Debug, Clone, PartialEq)]
+struct Route {
+    start: Position,
+    stops: Vec<Position>,
+}
+
+fn position_layout(name: &str) -> Layout {
+    let field = |name: &str| Layout {
+        name: String::from(name),
+        kind: LayoutKind::F32,
+        fields: None,
+        default: None,
+        field_number: None,
+    };
+    Layout {
+        name: String::from(name),
+        kind: LayoutKind::Struct,
+        fields: Some(vec![field("x"), field("y")]),
+        default: None,
+        field_number: None,
+    }
+}
+
+#[test]
+fn nested_struct_is_named_after_its_field() {
+    let fields = Route::get_layout().fields.unwrap();
+    assert_eq!(fields[0], position_layout("start"));
+    // Array elements take the name every array element gets.
+    assert_eq!(
+        fields[1].fields.as_ref().unwrap()[0],
+        position_layout("unnamed")
+    );
+    // The nested struct's own layout keeps the type name.
+    assert_eq!(Position::get_layout(), position_layout("Position"));
+}
+
+#[test]
+fn nested_struct_field_names_match_json() {
+    let route = Route {
+        start: Position { x: 1.0, y: 2.0 },
+        stops: vec![Position { x: 3.0, y: 4.0 }],
+    };
+    let layout = Route::get_layout();
+    let bytes = bincode::serialize(&route).unwrap();
+    let serial = project_kind::serialize(&layout, &bytes).unwrap();
+    assert_eq!(
+        serial,
+        r#"{"start":{"x":1.0,"y":2.0},"stops":[{"x":3.0,"y":4.0}]}"#
+    );
+    assert_eq!(
+        project_kind::get_path(&layout, &bytes, "stops[0].x").unwrap(),
+        serde_json::json!(3.0)
+    );
+}
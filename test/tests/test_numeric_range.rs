@@ -0,0 +1,117 @@
+use project_kind::{Error, HasLayout, Layout, LayoutKind, NumericPolicy};
+use project_kind_macros::component;
+
+#[component]
+#[derive(Debug, Clone, PartialEq)]
+struct Person {
+    name: String,
+    age: u8,
+    balance: i16,
+    speed: f32,
+    scores: Vec<u32>,
+}
+
+fn value_error(error: Error) -> (String, String) {
+    match error {
+        Error::Value { path, message } => (path, message),
+        error => panic!("unexpected error: {}", error),
+    }
+}
+
+#[test]
+fn integers_out_of_range() {
+    let person_layout = Person::get_layout();
+
+    let person_string = r#"{"name":"Bob","age":300,"balance":0,"speed":1.0,"scores":[]}"#;
+    let error = project_kind::deserialize(&person_layout, person_string).unwrap_err();
+    assert_eq!(
+        value_error(error),
+        (
+            String::from("age"),
+            String::from("300 is out of range for U8 (0..=255)")
+        )
+    );
+
+    let person_string = r#"{"name":"Bob","age":30,"balance":0,"speed":1.0,"scores":[1,-2]}"#;
+    let error = project_kind::deserialize(&person_layout, person_string).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "invalid value at `scores[1]`: -2 is out of range for U32 (0..=4294967295)"
+    );
+
+    let person_string = r#"{"name":"Bob","age":30,"balance":-32769,"speed":1.0,"scores":[]}"#;
+    let error = project_kind::deserialize(&person_layout, person_string).unwrap_err();
+    assert_eq!(
+        value_error(error).1,
+        "-32769 is out of range for I16 (-32768..=32767)"
+    );
+}
+
+#[test]
+fn floats_and_types() {
+    let person_layout = Person::get_layout();
+
+    let person_string = r#"{"name":"Bob","age":30,"balance":0,"speed":1e39,"scores":[]}"#;
+    let error = project_kind::deserialize(&person_layout, person_string).unwrap_err();
+    let (path, message) = value_error(error);
+    assert_eq!(path, "speed");
+    assert!(
+        message.starts_with("1e39 is out of range for F32"),
+        "{}",
+        message
+    );
+
+    let person_string = r#"{"name":"Bob","age":30,"balance":0,"speed":3.4028235e38,"scores":[]}"#;
+    let person_bytes = project_kind::deserialize(&person_layout, person_string).unwrap();
+    let person_typed = bincode::deserialize::<Person>(&person_bytes).unwrap();
+    assert_eq!(person_typed.speed, f32::MAX);
+
+    let person_string = r#"{"name":"Bob","age":30,"balance":0,"speed":-3.4028235e38,"scores":[]}"#;
+    let person_bytes = project_kind::deserialize(&person_layout, person_string).unwrap();
+    let person_typed = bincode::deserialize::<Person>(&person_bytes).unwrap();
+    assert_eq!(person_typed.speed, f32::MIN);
+
+    let person_string = r#"{"name":7,"age":30,"balance":0,"speed":1.0,"scores":[]}"#;
+    let error = project_kind::deserialize(&person_layout, person_string).unwrap_err();
+    assert_eq!(
+        value_error(error),
+        (
+            String::from("name"),
+            String::from("expected a string, found 7")
+        )
+    );
+
+    let person_string = r#"{"name":"Bob","age":2.5,"balance":0,"speed":1.0}"#;
+    let error = project_kind::deserialize(&person_layout, person_string).unwrap_err();
+    assert_eq!(value_error(error).1, "expected an integer, found 2.5");
+}
+
+#[test]
+fn wrapping_and_saturating_policies() {
+    let person_layout = Person::get_layout();
+    let person_string = r#"{"name":"Bob","age":300,"balance":-40000,"speed":1e39,"scores":[-1]}"#;
+
+    let person_bytes = project_kind::deserialize_with_policy(
+        &person_layout,
+        person_string,
+        NumericPolicy::Wrapping,
+    )
+    .unwrap();
+    let person_typed = bincode::deserialize::<Person>(&person_bytes).unwrap();
+    assert_eq!(person_typed.age, 44);
+    assert_eq!(person_typed.balance, 25536);
+    assert_eq!(person_typed.speed, f32::INFINITY);
+    assert_eq!(person_typed.scores, vec![u32::MAX]);
+
+    let person_bytes = project_kind::deserialize_with_policy(
+        &person_layout,
+        person_string,
+        NumericPolicy::Saturating,
+    )
+    .unwrap();
+    let person_typed = bincode::deserialize::<Person>(&person_bytes).unwrap();
+    assert_eq!(person_typed.age, u8::MAX);
+    assert_eq!(person_typed.balance, i16::MIN);
+    assert_eq!(person_typed.speed, f32::MAX);
+    assert_eq!(person_typed.scores, vec![0]);
+}
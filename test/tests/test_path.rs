@@ -0,0 +1,155 @@
+use project_kind::{HasLayout, Layout, LayoutKind, PathSegment};
+use project_kind_macros::component;
+use serde_json::json;
+
+#[component]
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Item {
+    name: String,
+    count: u16,
+}
+
+#[component]
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Person {
+    name: String,
+    inventory: Vec<Item>,
+    age: u8,
+}
+
+#[component]
+#[derive(Debug, Clone, PartialEq)]
+struct Car {
+    name: String,
+    speed: f32,
+}
+
+fn person() -> Person {
+    Person {
+        name: String::from("Andy"),
+        inventory: vec![
+            Item {
+                name: String::from("Sword"),
+                count: 1,
+            },
+            Item {
+                name: String::from("Arrow"),
+                count: 40,
+            },
+        ],
+        age: 27,
+    }
+}
+
+#[test]
+fn parse_paths() {
+    let segments = project_kind::parse_path("inventory[3].name").unwrap();
+    let segments_expected = vec![
+        PathSegment::Field(String::from("inventory")),
+        PathSegment::Index(3),
+        PathSegment::Field(String::from("name")),
+    ];
+    assert_eq!(segments, segments_expected);
+    assert_eq!(project_kind::parse_path("").unwrap(), vec![]);
+    assert_eq!(
+        project_kind::parse_path("[0][1]").unwrap(),
+        vec![PathSegment::Index(0), PathSegment::Index(1)]
+    );
+    assert!(project_kind::parse_path("inventory[x]").is_err());
+    assert!(project_kind::parse_path("inventory..name").is_err());
+}
+
+#[test]
+fn get_path() {
+    let person_layout = Person::get_layout();
+    let person_bytes = bincode::serialize(&person()).unwrap();
+
+    let value = project_kind::get_path(&person_layout, &person_bytes, "inventory[1].name").unwrap();
+    assert_eq!(value, json!("Arrow"));
+    let value = project_kind::get_path(&person_layout, &person_bytes, "age").unwrap();
+    assert_eq!(value, json!(27));
+    let value = project_kind::get_path(&person_layout, &person_bytes, "inventory[0]").unwrap();
+    assert_eq!(value, json!({"name": "Sword", "count": 1}));
+
+    let error = project_kind::get_path(&person_layout, &person_bytes, "inventory[2]").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "path error: index 2 out of bounds for `inventory` of length 2"
+    );
+    assert!(project_kind::get_path(&person_layout, &person_bytes, "inventory.name").is_err());
+    assert!(project_kind::get_path(&person_layout, &person_bytes, "height").is_err());
+}
+
+#[test]
+fn set_path() {
+    let person_layout = Person::get_layout();
+    let mut person_bytes = bincode::serialize(&person()).unwrap();
+
+    project_kind::set_path(
+        &person_layout,
+        &mut person_bytes,
+        "inventory[0].name",
+        json!("Longsword of Dawn"),
+    )
+    .unwrap();
+    project_kind::set_path(&person_layout, &mut person_bytes, "age", json!(28)).unwrap();
+    project_kind::set_path(
+        &person_layout,
+        &mut person_bytes,
+        "inventory",
+        json!([{"name": "Sword", "count": 1}, {"name": "Arrow", "count": 40}, {"name": "Bow", "count": 1}]),
+    )
+    .unwrap();
+    project_kind::set_path(
+        &person_layout,
+        &mut person_bytes,
+        "inventory[0].name",
+        json!("Longsword of Dawn"),
+    )
+    .unwrap();
+
+    let person_typed = bincode::deserialize::<Person>(&person_bytes).unwrap();
+    let mut person_typed_expected = person();
+    person_typed_expected.inventory[0].name = String::from("Longsword of Dawn");
+    person_typed_expected.inventory.push(Item {
+        name: String::from("Bow"),
+        count: 1,
+    });
+    person_typed_expected.age = 28;
+    assert_eq!(person_typed, person_typed_expected);
+
+    let error =
+        project_kind::set_path(&person_layout, &mut person_bytes, "age", json!(300)).unwrap_err();
+    assert!(error.to_string().starts_with("invalid value at `age`"));
+    assert_eq!(
+        bincode::deserialize::<Person>(&person_bytes).unwrap(),
+        person_typed_expected
+    );
+}
+
+#[test]
+fn set_path_checks_f32_range() {
+    let car_layout = Car::get_layout();
+    let car = Car {
+        name: String::from("Beetle"),
+        speed: 1.0,
+    };
+    let mut car_bytes = bincode::serialize(&car).unwrap();
+
+    let error =
+        project_kind::set_path(&car_layout, &mut car_bytes, "speed", json!(1e300)).unwrap_err();
+    assert!(
+        error
+            .to_string()
+            .starts_with("invalid value at `speed`: 1e300 is out of range for F32"),
+        "{}",
+        error
+    );
+    assert_eq!(bincode::deserialize::<Car>(&car_bytes).unwrap(), car);
+
+    project_kind::set_path(&car_layout, &mut car_bytes, "speed", json!(-3.4028235e38)).unwrap();
+    assert_eq!(
+        bincode::deserialize::<Car>(&car_bytes).unwrap().speed,
+        f32::MIN
+    );
+}
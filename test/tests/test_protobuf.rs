@@ -0,0 +1,274 @@
+use project_kind::{
+    deserialize_protobuf, serialize_protobuf, to_proto, Error, HasLayout, Layout, LayoutKind,
+};
+use project_kind_macros::component;
+use prost::Message;
+
+#[component]
+#[derive(Debug, Clone, PartialEq)]
+struct Position {
+    x: f32,
+    y: f32,
+}
+
+#[component]
+#[derive(Debug, Clone, PartialEq)]
+struct Item {
+    id: u16,
+    tags: Vec<String>,
+}
+
+#[component]
+#[derive(Debug, Clone, PartialEq)]
+struct Player {
+    name: String,
+    #[proto(number = 10)]
+    level: u8,
+    balance: i64,
+    alive: bool,
+    position: Position,
+    inventory: Vec<Item>,
+    scores: Vec<i32>,
+    grid: Vec<Vec<u64>>,
+    weight: f64,
+}
+
+// The same messages, declared for prost as the generated .proto describes them.
+#[derive(Clone, PartialEq, Message)]
+struct ProtoPosition {
+    #[prost(float, tag = "1")]
+    x: f32,
+    #[prost(float, tag = "2")]
+    y: f32,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct ProtoItem {
+    #[prost(uint32, tag = "1")]
+    id: u32,
+    #[prost(string, repeated, tag = "2")]
+    tags: Vec<String>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct ProtoGrid {
+    #[prost(uint64, repeated, tag = "1")]
+    items: Vec<u64>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct ProtoPlayer {
+    #[prost(string, tag = "1")]
+    name: String,
+    #[prost(uint32, tag = "10")]
+    level: u32,
+    #[prost(sint64, tag = "3")]
+    balance: i64,
+    #[prost(bool, tag = "4")]
+    alive: bool,
+    #[prost(message, optional, tag = "5")]
+    position: Option<ProtoPosition>,
+    #[prost(message, repeated, tag = "6")]
+    inventory: Vec<ProtoItem>,
+    #[prost(sint32, repeated, tag = "7")]
+    scores: Vec<i32>,
+    #[prost(message, repeated, tag = "8")]
+    grid: Vec<ProtoGrid>,
+    #[prost(double, tag = "9")]
+    weight: f64,
+}
+
+// Writes repeated scalars unpacked, and adds a field the layout lacks.
+#[derive(Clone, PartialEq, Message)]
+struct LoosePlayer {
+    #[prost(string, tag = "1")]
+    name: String,
+    #[prost(uint32, tag = "10")]
+    level: u32,
+    #[prost(sint32, repeated, packed = "false", tag = "7")]
+    scores: Vec<i32>,
+    #[prost(string, tag = "99")]
+    nickname: String,
+}
+
+#[test]
+fn proto_file_from_layout() {
+    assert_eq!(
+        Player::get_layout().fields.unwrap()[1].field_number,
+        Some(10)
+    );
+    assert_eq!(
+        to_proto(&[Player::get_layout()]).unwrap(),
+        "// Generated from kind layouts. Do not edit.
+syntax = \"proto3\";
+
+message Player {
+  string name = 1;
+  uint32 level = 10;
+  sint64 balance = 3;
+  bool alive = 4;
+  Position position = 5;
+  repeated Inventory inventory = 6;
+  repeated sint32 scores = 7;
+  repeated Grid grid = 8;
+  double weight = 9;
+  message Position {
+    float x = 1;
+    float y = 2;
+  }
+  message Inventory {
+    uint32 id = 1;
+    repeated string tags = 2;
+  }
+  message Grid {
+    repeated uint64 items = 1;
+  }
+}
+"
+    );
+
+    let mut layout = Player::get_layout();
+    layout.fields.as_mut().unwrap()[3].field_number = Some(19500);
+    assert_eq!(
+        to_proto(&[layout.clone()]),
+        Err(Error::Protobuf(String::from(
+            "field number 19500 of `alive` is reserved by protobuf"
+        )))
+    );
+    layout.fields.as_mut().unwrap()[3].field_number = Some(1);
+    assert_eq!(
+        serialize_protobuf(&layout, &[]).unwrap_err().to_string(),
+        "protobuf error: field number 1 of `alive` is used twice"
+    );
+}
+
+#[test]
+fn protobuf_matches_prost() {
+    let layout = Player::get_layout();
+    let player = Player {
+        name: String::from("Ann"),
+        level: 200,
+        balance: -5_000_000_000,
+        alive: true,
+        position: Position { x: 1.5, y: 0.0 },
+        inventory: vec![
+            Item {
+                id: 3,
+                tags: vec![String::from("rare"), String::new()],
+            },
+            Item {
+                id: 0,
+                tags: vec![],
+            },
+        ],
+        scores: vec![-1, 0, i32::MAX],
+        grid: vec![vec![1, u64::MAX], vec![]],
+        weight: 72.5,
+    };
+    let proto_player = ProtoPlayer {
+        name: String::from("Ann"),
+        level: 200,
+        balance: -5_000_000_000,
+        alive: true,
+        position: Some(ProtoPosition { x: 1.5, y: 0.0 }),
+        inventory: vec![
+            ProtoItem {
+                id: 3,
+                tags: vec![String::from("rare"), String::new()],
+            },
+            ProtoItem {
+                id: 0,
+                tags: vec![],
+            },
+        ],
+        scores: vec![-1, 0, i32::MAX],
+        grid: vec![
+            ProtoGrid {
+                items: vec![1, u64::MAX],
+            },
+            ProtoGrid { items: vec![] },
+        ],
+        weight: 72.5,
+    };
+    let bytes = project_kind::bincode::serialize(&player).unwrap();
+
+    let serial = serialize_protobuf(&layout, &bytes).unwrap();
+    assert_eq!(serial, proto_player.encode_to_vec());
+    assert_eq!(
+        ProtoPlayer::decode(serial.as_slice()).unwrap(),
+        proto_player
+    );
+    assert_eq!(deserialize_protobuf(&layout, &serial).unwrap(), bytes);
+
+    // An all-zero player is mostly left out, but its position is present.
+    let empty = ProtoPlayer {
+        position: Some(ProtoPosition::default()),
+        ..ProtoPlayer::default()
+    };
+    let empty_bytes = deserialize_protobuf(&layout, &[]).unwrap();
+    assert_eq!(
+        serialize_protobuf(&layout, &empty_bytes).unwrap(),
+        empty.encode_to_vec()
+    );
+}
+
+#[test]
+fn protobuf_decoding_is_lenient_but_checked() {
+    let layout = Player::get_layout();
+    let loose = LoosePlayer {
+        name: String::from("Bo"),
+        level: 7,
+        scores: vec![4, -4],
+        nickname: String::from("ignored"),
+    };
+    let mut serial = loose.encode_to_vec();
+    // Two occurrences of a message field merge.
+    serial.extend_from_slice(
+        &ProtoPlayer {
+            position: Some(ProtoPosition { x: 2.0, y: 0.0 }),
+            ..ProtoPlayer::default()
+        }
+        .encode_to_vec(),
+    );
+    serial.extend_from_slice(
+        &ProtoPlayer {
+            position: Some(ProtoPosition { x: 0.0, y: 3.0 }),
+            ..ProtoPlayer::default()
+        }
+        .encode_to_vec(),
+    );
+    let bytes = deserialize_protobuf(&layout, &serial).unwrap();
+    let player: Player = project_kind::bincode::deserialize(&bytes).unwrap();
+    assert_eq!(player.name, "Bo");
+    assert_eq!(player.level, 7);
+    assert_eq!(player.scores, vec![4, -4]);
+    assert_eq!(player.position, Position { x: 2.0, y: 3.0 });
+
+    let too_high = LoosePlayer {
+        level: 300,
+        ..LoosePlayer::default()
+    };
+    assert_eq!(
+        deserialize_protobuf(&layout, &too_high.encode_to_vec()),
+        Err(Error::Protobuf(String::from(
+            "300 is out of range for `level` (U8)"
+        )))
+    );
+    // Field 4 (`alive`) sent as a length-delimited string.
+    assert_eq!(
+        deserialize_protobuf(&layout, &[0x22, 0x01, b'x'])
+            .unwrap_err()
+            .to_string(),
+        "protobuf error: expected wire type 0 for `alive`, found 2"
+    );
+    assert!(deserialize_protobuf(&layout, &[0x0a, 0x05, b'A']).is_err());
+
+    let not_a_message = Layout {
+        name: String::from("Score"),
+        kind: LayoutKind::U8,
+        fields: None,
+        default: None,
+        field_number: None,
+    };
+    assert!(deserialize_protobuf(&not_a_message, &[]).is_err());
+}
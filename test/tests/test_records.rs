@@ -0,0 +1,94 @@
+use project_kind::{DecodeLimits, Error, HasLayout, Layout, LayoutKind, RecordIter};
+use project_kind_macros::component;
+use serde_json::json;
+
+#[component]
+#[derive(Debug, Clone, PartialEq)]
+struct Event {
+    frame: u32,
+    name: String,
+}
+
+fn event_bytes(frame: u32, name: &str) -> Vec<u8> {
+    bincode::serialize(&Event {
+        frame,
+        name: String::from(name),
+    })
+    .unwrap()
+}
+
+#[test]
+fn strict_trailing_bytes() {
+    let event_layout = Event::get_layout();
+    let mut event_bytes = event_bytes(1, "jump");
+    let event_length = event_bytes.len();
+    event_bytes.extend_from_slice(&[0xde, 0xad]);
+
+    let event_string = project_kind::serialize(&event_layout, &event_bytes).unwrap();
+    assert_eq!(event_string, r#"{"frame":1,"name":"jump"}"#);
+
+    let strict = DecodeLimits::strict();
+    match project_kind::serialize_with_limits(&event_layout, &event_bytes, &strict).unwrap_err() {
+        Error::Limit { offset, limit, .. } => {
+            assert_eq!(offset, event_length);
+            assert_eq!(limit, "max_trailing_bytes");
+        }
+        error => panic!("unexpected error: {}", error),
+    }
+
+    let event_string_strict =
+        project_kind::serialize_with_limits(&event_layout, &event_bytes[..event_length], &strict)
+            .unwrap();
+    assert_eq!(event_string_strict, event_string);
+}
+
+#[test]
+fn record_iter() {
+    let event_layout = Event::get_layout();
+    let mut log_bytes = Vec::new();
+    for (frame, name) in [(1, "jump"), (2, ""), (40, "land")] {
+        log_bytes.extend_from_slice(&event_bytes(frame, name));
+    }
+
+    let records = RecordIter::new(&event_layout, &log_bytes)
+        .collect::<Result<Vec<_>, Error>>()
+        .unwrap();
+    let offsets = records
+        .iter()
+        .map(|record| record.offset)
+        .collect::<Vec<_>>();
+    assert_eq!(offsets, vec![0, 16, 28]);
+    assert_eq!(records[1].bytes, event_bytes(2, "").as_slice());
+    assert_eq!(records[2].value, json!({"frame": 40, "name": "land"}));
+}
+
+#[test]
+fn record_iter_errors() {
+    let event_layout = Event::get_layout();
+    let mut log_bytes = event_bytes(1, "jump");
+    log_bytes.extend_from_slice(&event_bytes(2, "land")[..10]);
+
+    let mut records = RecordIter::new(&event_layout, &log_bytes);
+    assert_eq!(records.next().unwrap().unwrap().offset, 0);
+    assert_eq!(records.offset(), 16);
+    match records.next().unwrap().unwrap_err() {
+        Error::Decode { offset, path, .. } => {
+            assert_eq!(offset, 20);
+            assert_eq!(path, "name");
+        }
+        error => panic!("unexpected error: {}", error),
+    }
+    assert!(records.next().is_none());
+
+    let empty_layout = Layout {
+        name: String::from("Empty"),
+        kind: LayoutKind::Struct,
+        fields: Some(vec![]),
+        default: None,
+        field_number: None,
+    };
+    let mut records = RecordIter::new(&empty_layout, &[0]);
+    assert!(records.next().unwrap().is_err());
+    assert!(records.next().is_none());
+    assert!(RecordIter::new(&empty_layout, &[]).next().is_none());
+}
@@ -0,0 +1,75 @@
+use project_kind::{all_components, HasLayout, Layout, LayoutKind, Registry};
+use project_kind_macros::component;
+
+#[component(register)]
+#[derive(Debug, Clone, PartialEq)]
+struct Position {
+    x: f32,
+    y: f32,
+}
+
+#[component(default, register)]
+#[derive(Debug, Clone, PartialEq)]
+struct Health {
+    points: u16,
+}
+
+impl Default for Health {
+    fn default() -> Self {
+        Health { points: 100 }
+    }
+}
+
+// Not registered.
+#[component]
+#[derive(Debug, Clone, PartialEq)]
+struct Scratch {
+    value: u8,
+}
+
+mod v2 {
+    use project_kind::{Layout, LayoutKind};
+    use project_kind_macros::component;
+
+    #[component(register)]
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Position {
+        pub x: f32,
+        pub y: f32,
+        pub z: f32,
+    }
+}
+
+#[test]
+fn registered_components_are_listed() {
+    let names = all_components()
+        .into_iter()
+        .map(|layout| layout.name)
+        .collect::<Vec<_>>();
+    assert_eq!(names, vec!["Health", "Position", "Position"]);
+    assert!(!names.contains(&Scratch::get_layout().name));
+}
+
+#[test]
+fn registered_layouts_match_the_types() {
+    let layouts = all_components();
+    assert!(layouts.contains(&Position::get_layout()));
+    assert!(layouts.contains(&v2::Position::get_layout()));
+    // `default` still applies to registered components.
+    let health = Health::get_layout();
+    assert_eq!(health.fields.as_ref().unwrap()[0].default, Some(100.into()));
+    assert!(layouts.contains(&health));
+}
+
+#[test]
+fn registry_from_components() {
+    let registry = Registry::from_components().unwrap();
+    assert_eq!(registry.len(), 3);
+    assert_eq!(registry.versions("Position").count(), 2);
+    assert_eq!(registry.layout("Health"), Some(&Health::get_layout()));
+    assert_eq!(
+        registry.get("Position", v2::Position::get_layout().fingerprint()),
+        Some(&v2::Position::get_layout())
+    );
+    assert_eq!(registry.layout("Scratch"), None);
+}
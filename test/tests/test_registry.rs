@@ -0,0 +1,115 @@
+use project_kind::{Error, HasLayout, Layout, LayoutKind, Registry};
+use project_kind_macros::component;
+use serde_json::json;
+
+#[component]
+#[derive(Debug, Clone, PartialEq)]
+struct Position {
+    x: f32,
+    y: f32,
+}
+
+#[component]
+#[derive(Debug, Clone, PartialEq)]
+struct Velocity {
+    x: f32,
+    y: f32,
+}
+
+#[component]
+#[derive(Debug, Clone, PartialEq)]
+struct Person {
+    name: String,
+    age: u8,
+}
+
+mod v2 {
+    use project_kind::{Layout, LayoutKind};
+    use project_kind_macros::component;
+
+    #[component]
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Person {
+        pub name: String,
+        pub age: u8,
+        pub email: String,
+    }
+}
+
+#[test]
+fn registry_lookup_by_name_and_fingerprint() {
+    let mut registry = Registry::new();
+    assert!(registry.is_empty());
+    let position_fingerprint = registry.register::<Position>().unwrap();
+    let velocity_fingerprint = registry.register::<Velocity>().unwrap();
+    let person_fingerprint = registry.register::<Person>().unwrap();
+
+    assert_eq!(registry.len(), 3);
+    assert_eq!(registry.layout("Person"), Some(&Person::get_layout()));
+    assert_eq!(registry.layout("Nobody"), None);
+    assert_eq!(
+        registry.get("Position", position_fingerprint),
+        Some(&Position::get_layout())
+    );
+    assert_eq!(registry.get("Position", person_fingerprint), None);
+
+    // Components of the same shape share a fingerprint.
+    assert_eq!(position_fingerprint, velocity_fingerprint);
+    let names = registry
+        .by_fingerprint(position_fingerprint)
+        .map(|layout| layout.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(names, vec!["Position", "Velocity"]);
+
+    let names = registry
+        .iter()
+        .map(|layout| layout.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(names, vec!["Position", "Velocity", "Person"]);
+}
+
+#[test]
+fn registry_keeps_versions_of_a_name() {
+    let mut registry = Registry::new();
+    let v1 = registry.register::<Person>().unwrap();
+    let v2 = registry.register::<v2::Person>().unwrap();
+    assert_ne!(v1, v2);
+
+    assert_eq!(registry.layout("Person"), Some(&v2::Person::get_layout()));
+    assert_eq!(registry.get("Person", v1), Some(&Person::get_layout()));
+    let fingerprints = registry
+        .versions("Person")
+        .map(|layout| layout.fingerprint())
+        .collect::<Vec<_>>();
+    assert_eq!(fingerprints, vec![v1, v2]);
+    assert_eq!(registry.versions("Nobody").count(), 0);
+    assert_eq!((&registry).into_iter().count(), 2);
+}
+
+#[test]
+fn registry_rejects_conflicting_layouts() {
+    let mut registry = Registry::new();
+    let fingerprint = registry.register::<Person>().unwrap();
+    // Registering the same layout again is fine.
+    assert_eq!(registry.register::<Person>(), Ok(fingerprint));
+    assert_eq!(registry.len(), 1);
+
+    let mut layout = Person::get_layout();
+    layout.fields.as_mut().unwrap()[1].default = Some(json!(18));
+    assert_eq!(
+        registry.register_layout(layout),
+        Err(Error::Conflict {
+            name: String::from("Person"),
+            fingerprint,
+        })
+    );
+    assert_eq!(registry.layout("Person"), Some(&Person::get_layout()));
+    assert_eq!(
+        Error::Conflict {
+            name: String::from("Person"),
+            fingerprint: 0xab,
+        }
+        .to_string(),
+        "a different layout is already registered as `Person` with fingerprint 00000000000000ab"
+    );
+}
@@ -0,0 +1,136 @@
+use project_kind::{serialize, Error, HasLayout, Layout};
+use serde_json::{json, Value};
+
+mod generated {
+    include!("generated/player.rs");
+}
+
+fn named(name: &str, mut layout: Value) -> Value {
+    layout["name"] = Value::from(name);
+    layout
+}
+
+fn point(kind: &str) -> Value {
+    json!({"name": "", "kind": "Struct", "fields": [
+        {"name": "x", "kind": kind, "fields": null},
+        {"name": "y", "kind": kind, "fields": null},
+    ]})
+}
+
+// Two addresses sharing one struct, and two differently shaped positions.
+fn player_layout() -> Layout {
+    let address = json!({"name": "", "kind": "Struct", "fields": [
+        {"name": "street", "kind": "String", "fields": null},
+        {"name": "number", "kind": "U16", "fields": null},
+    ]});
+    let item = json!({"name": "item", "kind": "Struct", "fields": [
+        {"name": "id", "kind": "U32", "fields": null},
+        named("position", point("I32")),
+    ]});
+    serde_json::from_value(json!({"name": "player", "kind": "Struct", "fields": [
+        {"name": "name", "kind": "String", "fields": null, "default": "anonymous"},
+        named("position", point("F32")),
+        named("home", address.clone()),
+        named("work", address),
+        {"name": "inventory", "kind": "Array", "fields": [item]},
+        {"name": "scores", "kind": "Array", "fields": [
+            {"name": "round", "kind": "Array", "fields": [
+                {"name": "score", "kind": "U64", "fields": null},
+            ]},
+        ]},
+    ]}))
+    .unwrap()
+}
+
+#[test]
+fn rust_source_of_nested_structs() {
+    let layout = player_layout();
+    assert_eq!(
+        layout.to_rust_source().unwrap(),
+        include_str!("generated/player.rs")
+    );
+
+    // The generated types derive a layout of the same shape.
+    let generated_layout = generated::Player::get_layout();
+    assert_eq!(generated_layout.name, "Player");
+    assert_eq!(generated_layout.fingerprint(), layout.fingerprint());
+
+    // Protobuf field numbers carry over as attributes.
+    let mut numbered_layout = layout.clone();
+    numbered_layout.fields.as_mut().unwrap()[0].field_number = Some(7);
+    assert!(numbered_layout
+        .to_rust_source()
+        .unwrap()
+        .contains("    #[proto(number = 7)]\n    pub name: String,\n"));
+}
+
+#[test]
+fn rust_source_types_read_the_original_bytes() {
+    let player = generated::Player {
+        name: String::from("Ann"),
+        position: generated::Position { x: 1.5, y: -2.0 },
+        home: generated::Home {
+            street: String::from("Elm"),
+            number: 4,
+        },
+        work: generated::Home {
+            street: String::from("Oak"),
+            number: 10,
+        },
+        inventory: vec![generated::Inventory {
+            id: 7,
+            position: generated::Position2 { x: -1, y: 3 },
+        }],
+        scores: vec![vec![1, 2], vec![]],
+    };
+    let bytes = project_kind::bincode::serialize(&player).unwrap();
+    let value: Value = serde_json::from_str(&serialize(&player_layout(), &bytes).unwrap()).unwrap();
+    assert_eq!(
+        value,
+        json!({
+            "name": "Ann",
+            "position": {"x": 1.5, "y": -2.0},
+            "home": {"street": "Elm", "number": 4},
+            "work": {"street": "Oak", "number": 10},
+            "inventory": [{"id": 7, "position": {"x": -1, "y": 3}}],
+            "scores": [[1, 2], []],
+        })
+    );
+}
+
+#[test]
+fn rust_source_rejects_field_names_components_cannot_express() {
+    let layout_with_field = |field_name: &str| -> Layout {
+        serde_json::from_value(json!({"name": "Outer", "kind": "Struct", "fields": [
+            {"name": "items", "kind": "Array", "fields": [
+                {"name": "", "kind": "Struct", "fields": [
+                    {"name": "id", "kind": "U8", "fields": null},
+                    {"name": field_name, "kind": "U8", "fields": null},
+                ]},
+            ]},
+        ]}))
+        .unwrap()
+    };
+
+    assert_eq!(
+        layout_with_field("spawn-point").to_rust_source(),
+        Err(Error::Identifier {
+            path: String::from("items.spawn-point"),
+            message: String::from("`spawn-point` is not a Rust identifier"),
+        })
+    );
+    assert_eq!(
+        layout_with_field("type")
+            .to_rust_source()
+            .unwrap_err()
+            .to_string(),
+        "invalid identifier at `items.type`: `type` is a Rust keyword"
+    );
+    assert_eq!(
+        layout_with_field("id")
+            .to_rust_source()
+            .unwrap_err()
+            .to_string(),
+        "invalid identifier at `items.id`: duplicate field `id`"
+    );
+}
@@ -0,0 +1,82 @@
+use project_kind::{HasLayout, Layout, LayoutBytes, LayoutKind, LayoutSeed};
+use project_kind_macros::component;
+use serde::de::DeserializeSeed;
+
+#[component]
+#[derive(Debug, Clone, PartialEq)]
+struct Position {
+    x: f32,
+    y: f32,
+}
+
+#[component]
+#[derive(Debug, Clone, PartialEq)]
+struct Person {
+    name: String,
+    age: u8,
+    alive: bool,
+    position: Position,
+    scores: Vec<i64>,
+}
+
+fn person() -> Person {
+    Person {
+        name: String::from("Andy"),
+        age: 27,
+        alive: true,
+        position: Position { x: 1.5, y: -2.0 },
+        scores: vec![-3, 40, 500],
+    }
+}
+
+#[test]
+fn serialize_keeps_layout_field_order() {
+    let person_layout = Person::get_layout();
+    let person_bytes = bincode::serialize(&person()).unwrap();
+
+    let person_string = serde_json::to_string(&LayoutBytes(&person_layout, &person_bytes)).unwrap();
+
+    let person_string_expected = r#"{"name":"Andy","age":27,"alive":true,"position":{"x":1.5,"y":-2.0},"scores":[-3,40,500]}"#;
+    assert_eq!(person_string, person_string_expected);
+}
+
+#[test]
+fn deserialize_seed_round_trip() {
+    let person_layout = Person::get_layout();
+    let person_bytes = bincode::serialize(&person()).unwrap();
+
+    let person_string = serde_json::to_string(&LayoutBytes(&person_layout, &person_bytes)).unwrap();
+    let mut deserializer = serde_json::Deserializer::from_str(&person_string);
+    let person_bytes_after = LayoutSeed(&person_layout)
+        .deserialize(&mut deserializer)
+        .unwrap();
+    assert_eq!(person_bytes, person_bytes_after);
+
+    let person_value = serde_json::to_value(LayoutBytes(&person_layout, &person_bytes)).unwrap();
+    let person_bytes_after = LayoutSeed(&person_layout)
+        .deserialize(person_value)
+        .unwrap();
+    assert_eq!(person_bytes, person_bytes_after);
+
+    let person_typed_after = bincode::deserialize::<Person>(&person_bytes_after).unwrap();
+    assert_eq!(person(), person_typed_after);
+}
+
+#[test]
+fn deserialize_seed_reports_errors() {
+    let person_layout = Person::get_layout();
+
+    let missing = r#"{"name":"Andy","age":27,"alive":true,"scores":[]}"#;
+    let mut deserializer = serde_json::Deserializer::from_str(missing);
+    let error = LayoutSeed(&person_layout)
+        .deserialize(&mut deserializer)
+        .unwrap_err();
+    assert!(error.to_string().contains("missing field `position`"));
+
+    let out_of_range =
+        r#"{"name":"Andy","age":300,"alive":true,"position":{"x":0,"y":0},"scores":[]}"#;
+    let mut deserializer = serde_json::Deserializer::from_str(out_of_range);
+    assert!(LayoutSeed(&person_layout)
+        .deserialize(&mut deserializer)
+        .is_err());
+}
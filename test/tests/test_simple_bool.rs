@@ -16,7 +16,11 @@ fn serialize_deserialize_single() {
             name: String::from("is_old"),
             kind: LayoutKind::Bool,
             fields: None,
+            default: None,
+            field_number: None,
         }]),
+        default: None,
+        field_number: None,
     };
 
     let person_typed = Person { is_old: false };
@@ -25,11 +29,12 @@ fn serialize_deserialize_single() {
     let person_bytes = bincode::serialize(&person_typed).unwrap();
     let person_two_bytes = bincode::serialize(&person_two_typed).unwrap();
 
-    let person_string = project_kind::serialize(&person_layout, &person_bytes);
-    let person_two_string = project_kind::serialize(&person_layout, &person_two_bytes);
+    let person_string = project_kind::serialize(&person_layout, &person_bytes).unwrap();
+    let person_two_string = project_kind::serialize(&person_layout, &person_two_bytes).unwrap();
 
-    let person_bytes_after = project_kind::deserialize(&person_layout, &person_string);
-    let person_two_bytes_after = project_kind::deserialize(&person_layout, &person_two_string);
+    let person_bytes_after = project_kind::deserialize(&person_layout, &person_string).unwrap();
+    let person_two_bytes_after =
+        project_kind::deserialize(&person_layout, &person_two_string).unwrap();
 
     let person_typed_after = bincode::deserialize::<Person>(&person_bytes_after).unwrap();
     let person_two_typed_after = bincode::deserialize::<Person>(&person_two_bytes_after).unwrap();
@@ -55,12 +60,18 @@ fn serialize_deserialize_array() {
             name: String::from("is_old"),
             kind: LayoutKind::Bool,
             fields: None,
+            default: None,
+            field_number: None,
         }]),
+        default: None,
+        field_number: None,
     };
     let person_array_layout = Layout {
         name: String::from("Persons"),
         kind: LayoutKind::Array,
         fields: Some(vec![person_layout]),
+        default: None,
+        field_number: None,
     };
 
     let person_typed = Person { is_old: true };
@@ -70,10 +81,11 @@ fn serialize_deserialize_array() {
 
     let person_array_bytes = bincode::serialize(&person_array_typed).unwrap();
 
-    let person_array_string = project_kind::serialize(&person_array_layout, &person_array_bytes);
+    let person_array_string =
+        project_kind::serialize(&person_array_layout, &person_array_bytes).unwrap();
 
     let person_array_bytes_after =
-        project_kind::deserialize(&person_array_layout, &person_array_string);
+        project_kind::deserialize(&person_array_layout, &person_array_string).unwrap();
 
     let person_array_typed_after =
         bincode::deserialize::<Vec<Person>>(&person_array_bytes_after).unwrap();
@@ -99,7 +111,11 @@ fn auto_implement_layout() {
             name: String::from("is_old"),
             kind: LayoutKind::Bool,
             fields: None,
+            default: None,
+            field_number: None,
         }]),
+        default: None,
+        field_number: None,
     };
     assert_eq!(actual_layout, expected_layout);
 }
@@ -0,0 +1,245 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+use project_kind::{to_typescript, HasLayout, Layout, LayoutKind, TypeScriptOptions};
+use project_kind_macros::component;
+use regex::Regex;
+use serde_json::{json, Value};
+
+#[component]
+#[derive(Debug, Clone, PartialEq)]
+struct Position {
+    x: f32,
+    y: f32,
+}
+
+#[component]
+#[derive(Debug, Clone, PartialEq)]
+struct Person {
+    name: String,
+    age: u8,
+    balance: i64,
+    position: Position,
+    favorite_numbers: Vec<u64>,
+}
+
+#[component]
+#[derive(Debug, Clone, PartialEq)]
+struct Sample {
+    on: bool,
+    tiny: i8,
+    small: u16,
+    medium: i32,
+    large: u32,
+    precise: f64,
+    label: String,
+    path: Vec<Position>,
+    chunks: Vec<Vec<u8>>,
+    person: Person,
+}
+
+mod v2 {
+    use project_kind::{Layout, LayoutKind};
+    use project_kind_macros::component;
+
+    #[component]
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Position {
+        pub x: f32,
+        pub y: f32,
+        pub z: f32,
+    }
+}
+
+// Node cannot run TypeScript, so this removes the type syntax the generator
+// emits: declarations, annotations and the `private` and `<T>` markers.
+fn strip_types(source: &str) -> String {
+    let rules = [
+        (r"(?ms)^export interface \w+ \{.*?^\}\n", ""),
+        (r"(?m)^export type .*\n", ""),
+        (r"(?m)^  private \w+: [^=;]+;\n", ""),
+        (r"private ", ""),
+        (r"(\w+)<T>\(", "$1("),
+        (r": \(\) => T\b", ""),
+        (r": \(value: T\) => void", ""),
+        (r"\): [\w\[\]]+ \{", ") {"),
+        (r"(\w+): (bigint \| number|[\w\[\]]+)([,)])", "$1$3"),
+        (r"(const \w+): [\w\[\]]+ =", "$1 ="),
+    ];
+    let mut source = source.to_string();
+    for (pattern, replacement) in rules {
+        source = Regex::new(pattern)
+            .unwrap()
+            .replace_all(&source, replacement)
+            .into_owned();
+    }
+    source
+}
+
+// Runs the generated module with `script` appended and parses its output.
+fn run_node(name: &str, source: &str, script: &str) -> Value {
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(format!("{}.mjs", name));
+    fs::write(&path, format!("{}\n{}", strip_types(source), script)).unwrap();
+    let output = Command::new("node")
+        .arg(&path)
+        .output()
+        .expect("Could not run node");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+#[test]
+fn typescript_interfaces() {
+    let score_layout = Layout {
+        name: String::from("Score"),
+        kind: LayoutKind::U32,
+        fields: None,
+        default: None,
+        field_number: None,
+    };
+    let source = to_typescript(
+        &[Person::get_layout(), score_layout],
+        &TypeScriptOptions::default(),
+    );
+
+    assert!(source.starts_with("// Generated from kind layouts. Do not edit.\n"));
+    assert!(source.contains(
+        "export interface Person {\n  name: string;\n  age: number;\n  balance: number;\n  \
+         position: {\n    x: number;\n    y: number;\n  };\n  favorite_numbers: number[];\n}\n"
+    ));
+    assert!(source.contains("export type Score = number;\n"));
+    assert!(!source.contains("decodePerson"));
+}
+
+#[test]
+fn typescript_bigint_option() {
+    let options = TypeScriptOptions {
+        bigint: true,
+        ..TypeScriptOptions::default()
+    };
+    let source = to_typescript(&[Person::get_layout()], &options);
+
+    assert!(source.contains("  age: number;\n"));
+    assert!(source.contains("  balance: bigint | number;\n"));
+    assert!(source.contains("  favorite_numbers: (bigint | number)[];\n"));
+}
+
+#[test]
+fn typescript_codec() {
+    let options = TypeScriptOptions {
+        bigint: false,
+        codec: true,
+    };
+    let source = to_typescript(&[Person::get_layout()], &options);
+
+    assert!(source.contains("class KindReader {"));
+    assert!(source.contains("class KindWriter {"));
+    assert!(source.contains("export function decodePerson(bytes: Uint8Array): Person {"));
+    assert!(source.contains("export function encodePerson(value: Person): Uint8Array {"));
+    assert!(source.contains("    balance: Number(r.i64()),\n"));
+    assert!(source.contains("  w.array(value.favorite_numbers, (e0) => w.u64(e0));\n"));
+}
+
+#[test]
+fn typescript_codec_round_trip() {
+    let sample = Sample {
+        on: true,
+        tiny: -5,
+        small: 65_000,
+        medium: -2_000_000,
+        large: 4_000_000_000,
+        precise: 0.1,
+        label: String::from("héllo ✓"),
+        path: vec![Position { x: 1.5, y: -2.25 }],
+        chunks: vec![vec![1, 2, 255], vec![]],
+        person: Person {
+            name: String::from("Ann"),
+            age: 42,
+            balance: -9_007_199_254_740_991,
+            position: Position { x: 0.0, y: 3.0 },
+            favorite_numbers: vec![0, 18_446_744_073_709_551_615],
+        },
+    };
+    let layout = Sample::get_layout();
+    let bytes = bincode::serialize(&sample).unwrap();
+
+    let options = TypeScriptOptions {
+        bigint: true,
+        codec: true,
+    };
+    let source = to_typescript(std::slice::from_ref(&layout), &options);
+    let script = format!(
+        "const bytes = Uint8Array.from({:?});
+const value = decodeSample(bytes);
+const errors = [bytes.subarray(0, bytes.length - 1), Uint8Array.from([...bytes, 0])].map((input) => {{
+  try {{ decodeSample(input); return null; }} catch (error) {{ return error.message; }}
+}});
+console.log(JSON.stringify({{
+  value,
+  encoded: Array.from(encodeSample(value)),
+  errors,
+}}, (key, field) => typeof field === \"bigint\" ? `${{field}}` : field));",
+        bytes
+    );
+    let output = run_node("codec_round_trip", &source, &script);
+
+    // 64-bit integers decode to bigints, which are printed as strings.
+    let mut value = output["value"].clone();
+    let person = &mut value["person"];
+    let balance = person["balance"].as_str().unwrap().parse::<i64>().unwrap();
+    person["balance"] = json!(balance);
+    let numbers = person["favorite_numbers"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|number| number.as_str().unwrap().parse::<u64>().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(numbers, sample.person.favorite_numbers);
+    person["favorite_numbers"] = json!(numbers);
+    // JavaScript prints `0.0` as `0`, so compare the bytes the value encodes to.
+    assert_eq!(
+        project_kind::deserialize(&layout, &value.to_string()).unwrap(),
+        bytes
+    );
+    assert_eq!(output["encoded"], json!(bytes));
+    assert_eq!(
+        output["errors"],
+        json!([
+            // The last u64 is cut short.
+            format!("truncated at byte {}: needs 8 bytes", bytes.len() - 8),
+            "1 trailing bytes",
+        ])
+    );
+}
+
+#[test]
+fn typescript_unique_names() {
+    let options = TypeScriptOptions {
+        bigint: false,
+        codec: true,
+    };
+    let mut reader = Position::get_layout();
+    reader.name = String::from("KindReader");
+    let source = to_typescript(
+        &[Position::get_layout(), v2::Position::get_layout(), reader],
+        &options,
+    );
+    assert!(source.contains("export interface Position {\n  x: number;\n  y: number;\n}\n"));
+    assert!(source
+        .contains("export interface Position2 {\n  x: number;\n  y: number;\n  z: number;\n}\n"));
+    assert!(source.contains("export interface KindReader2 {"));
+    assert!(source.contains("export function decodePosition2(bytes: Uint8Array): Position2 {"));
+    assert_eq!(source.matches("export interface Position {").count(), 1);
+
+    let output = run_node(
+        "unique_names",
+        &source,
+        "console.log(JSON.stringify(decodePosition2(encodePosition2({ x: 1, y: 2, z: 3 }))));",
+    );
+    assert_eq!(output, json!({"x": 1, "y": 2, "z": 3}));
+}
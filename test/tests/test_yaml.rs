@@ -0,0 +1,87 @@
+use project_kind::{Error, HasLayout, Layout, LayoutKind};
+use project_kind_macros::component;
+
+#[component]
+#[derive(Debug, Clone, PartialEq)]
+struct Position {
+    x: f32,
+    y: f32,
+}
+
+#[component]
+#[derive(Debug, Clone, PartialEq)]
+struct Person {
+    name: String,
+    age: u8,
+    position: Position,
+    favorite_names: Vec<String>,
+}
+
+#[test]
+fn serialize_deserialize_yaml() {
+    let person_layout = Person::get_layout();
+    let person_typed = Person {
+        name: String::from("Andy"),
+        age: 27,
+        position: Position { x: 1.5, y: -2.0 },
+        favorite_names: vec![String::from("Bob"), String::from("Chloe")],
+    };
+
+    let person_bytes = bincode::serialize(&person_typed).unwrap();
+    let person_string = project_kind::serialize_yaml(&person_layout, &person_bytes).unwrap();
+
+    let person_string_expected = "name: Andy
+age: 27
+position:
+  x: 1.5
+  y: -2.0
+favorite_names:
+- Bob
+- Chloe
+";
+    assert_eq!(person_string, person_string_expected);
+
+    let person_bytes_after =
+        project_kind::deserialize_yaml(&person_layout, &person_string).unwrap();
+    let person_typed_after = bincode::deserialize::<Person>(&person_bytes_after).unwrap();
+    assert_eq!(person_typed, person_typed_after);
+    assert_eq!(person_bytes, person_bytes_after);
+}
+
+#[test]
+fn deserialize_yaml_with_comments() {
+    let person_layout = Person::get_layout();
+    let person_string = "# Hand edited
+favorite_names: [] # nobody
+position: { x: 0, y: 3 }
+age: 50
+name: Barbara
+";
+    let person_bytes = project_kind::deserialize_yaml(&person_layout, person_string).unwrap();
+    let person_typed = bincode::deserialize::<Person>(&person_bytes).unwrap();
+    let person_typed_expected = Person {
+        name: String::from("Barbara"),
+        age: 50,
+        position: Position { x: 0.0, y: 3.0 },
+        favorite_names: vec![],
+    };
+    assert_eq!(person_typed, person_typed_expected);
+}
+
+#[test]
+fn deserialize_yaml_reports_location() {
+    let person_layout = Person::get_layout();
+    let person_string = "name: Andy
+age: 300
+position: { x: 0, y: 0 }
+favorite_names: []
+";
+    let error = project_kind::deserialize_yaml(&person_layout, person_string).unwrap_err();
+    match error {
+        Error::Yaml { line, column, .. } => {
+            assert_eq!(line, Some(2));
+            assert_eq!(column, Some(6));
+        }
+        error => panic!("unexpected error: {}", error),
+    }
+}